@@ -0,0 +1,38 @@
+//! Compiles the scripts in a Script config file into a script bundle.
+//!
+//! ```text
+//! gobu-bundle scripts.toml scripts.bundle
+//! ```
+//!
+//! The bundle can then be loaded using ``GameBuilder::bundle`` instead of the Script config file.
+
+extern crate gobu;
+
+use std::{
+    env,
+    process,
+};
+use gobu::script::bundle::compile_bundle;
+
+const USAGE: &str = "\
+Usage: gobu-bundle <SCRIPTS> <BUNDLE>
+
+Parses every script in the Script config file SCRIPTS and writes them into the bundle file BUNDLE.
+Paths are relative to the directory that gobu-bundle is run from, just like the game.";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{}", USAGE);
+        return
+    }
+    if args.len() != 2 {
+        eprintln!("error: expected a Script config file and a bundle file\n\n{}", USAGE);
+        process::exit(2);
+    }
+    if let Err(e) = compile_bundle(&args[0], &args[1]) {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+    println!("Wrote {}", args[1]);
+}
@@ -0,0 +1,71 @@
+//! Checks the config files and scripts of a game without opening a window.
+//!
+//! ```text
+//! gobu-check --characters characters.toml --backgrounds backgrounds.toml --scripts scripts.toml
+//! ```
+//!
+//! Every problem that is found is printed and the exit code is 1 if there were any.
+
+extern crate gobu;
+
+use std::{
+    env,
+    process,
+};
+use gobu::check::Project;
+
+const USAGE: &str = "\
+Usage: gobu-check [OPTIONS]
+
+Checks the config files of a game and the scripts that they load.
+Paths are relative to the directory that gobu-check is run from, just like the game.
+
+Options:
+    --gui <FILE>            The GUI config file
+    --characters <FILE>     The Character config file
+    --backgrounds <FILE>    The Background config file
+    --scripts <FILE>        The Script config file
+    --input <FILE>          The Input config file
+    --music <FILE>          The Music config file
+    --transitions <FILE>    The Transition config file
+    -h, --help              Prints this message";
+
+fn main() {
+    let mut project = Project::new();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "-h" || arg == "--help" {
+            println!("{}", USAGE);
+            return
+        }
+        let file = match args.next() {
+            Some(file) => file,
+            None => usage_error(&format!("'{}' needs a file", arg)),
+        };
+        project = match arg.as_str() {
+            "--gui" => project.gui(file),
+            "--characters" => project.characters(file),
+            "--backgrounds" => project.backgrounds(file),
+            "--scripts" => project.scripts(file),
+            "--input" => project.input(file),
+            "--music" => project.music(file),
+            "--transitions" => project.transitions(file),
+            _ => usage_error(&format!("unknown option '{}'", arg)),
+        };
+    }
+    let issues = project.check();
+    if issues.is_empty() {
+        println!("No problems found.");
+        return
+    }
+    for issue in issues.iter() {
+        eprintln!("{}\n", issue);
+    }
+    eprintln!("{} problem{} found.", issues.len(), if issues.len() == 1 { "" } else { "s" });
+    process::exit(1);
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    process::exit(2);
+}
@@ -42,6 +42,8 @@ pub struct CharacterEntity {
     pub visible: bool,
     /// The name of the entity.
     pub name: String,
+    /// The name of the state of the character that is currently being shown.
+    pub state: String,
     /// The offset of the images so that a specific point on the image can be used as the centre.
     /// Set to (0, 0) by default.
     pub offset: Pos,
@@ -96,7 +98,9 @@ impl CharacterEntity {
             if let Some(ref mut e) = self.anim {
                 e.update(&mut self.image, delta_time)
             } else {
-                TransResult::Continue
+                // The animation may have been finished early so the entity still has to be
+                // killed or hidden.
+                TransResult::Finished
             }
         };
         if result == TransResult::Finished {
@@ -164,6 +168,7 @@ impl Character {
             },
             visible: true,
             name,
+            state: self.default.clone(),
             offset: self.offset,
             anim: None,
             to_be_hidden: false,
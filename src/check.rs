@@ -0,0 +1,317 @@
+//! Checks the config files of a game without opening a window.
+//!
+//! This is what the ``gobu-check`` binary uses. It reads the same TOML files as [`GameBuilder`],
+//! checks that the files they point to exist, parses every script and then checks that the
+//! names used by the scripts exist.
+//!
+//! Like the game, paths in the config files are relative to the directory that it is run from.
+//!
+//! [`GameBuilder`]: ../util/struct.GameBuilder.html
+
+use std::{
+    fmt,
+    fs::File,
+    io::Read,
+    path::Path,
+    collections::{
+        BTreeMap,
+        HashMap,
+        HashSet,
+    },
+};
+use toml;
+
+use super::{
+    error::{
+        ConfigImportError,
+        ScriptImportError,
+        ScriptParseError,
+    },
+    gui::textbox::TextBoxBuilder,
+    script::{
+        Script,
+        parser::translate_with_source,
+        validate::{
+            Diagnostic,
+            Names,
+        },
+    },
+    util::load::{
+        parse_characters_from_str,
+        parse_backgrounds_from_str,
+        parse_music_from_str,
+        load_input_from_str,
+        load_transitions_from_str,
+    },
+};
+
+/// A problem that was found while checking a game.
+#[derive(Debug)]
+pub enum Issue {
+    /// Something is wrong with a config file or something that it points to.
+    /// (File, Message)
+    Config(String, String),
+    /// A script couldn't be parsed.
+    Parse(ScriptParseError),
+    /// A step of a script uses a name that doesn't exist.
+    Script(Diagnostic),
+}
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Issue::Config(ref file, ref message) => write!(f, "{}: {}", file, message),
+            Issue::Parse(ref e) => write!(f, "{}", e),
+            Issue::Script(ref d) => write!(f, "{}", d),
+        }
+    }
+}
+
+/// The config files of a game that are checked.
+/// This takes the same files as [`GameBuilder`](../util/struct.GameBuilder.html).
+#[derive(Debug, Clone, Default)]
+pub struct Project {
+    /// The path to the GUI config file.
+    pub gui: Option<String>,
+    /// The path to the Character config file.
+    pub characters: Option<String>,
+    /// The path to the Background config file.
+    pub backgrounds: Option<String>,
+    /// The path to the Script config file.
+    pub scripts: Option<String>,
+    /// The path to the Input config file.
+    pub input: Option<String>,
+    /// The path to the Music config file.
+    pub music: Option<String>,
+    /// The path to the Transition config file.
+    pub transitions: Option<String>,
+}
+impl Project {
+    /// Create a new [`Project`] without any config files.
+    pub fn new() -> Project {
+        Project::default()
+    }
+    /// Set the path to the GUI config file.
+    pub fn gui<S: ToString>(mut self, path: S) -> Self {
+        self.gui = Some(path.to_string());
+        self
+    }
+    /// Set the path to the Character config file.
+    pub fn characters<S: ToString>(mut self, path: S) -> Self {
+        self.characters = Some(path.to_string());
+        self
+    }
+    /// Set the path to the Background config file.
+    pub fn backgrounds<S: ToString>(mut self, path: S) -> Self {
+        self.backgrounds = Some(path.to_string());
+        self
+    }
+    /// Set the path to the Script config file.
+    pub fn scripts<S: ToString>(mut self, path: S) -> Self {
+        self.scripts = Some(path.to_string());
+        self
+    }
+    /// Set the path to the Input config file.
+    pub fn input<S: ToString>(mut self, path: S) -> Self {
+        self.input = Some(path.to_string());
+        self
+    }
+    /// Set the path to the Music config file.
+    pub fn music<S: ToString>(mut self, path: S) -> Self {
+        self.music = Some(path.to_string());
+        self
+    }
+    /// Set the path to the Transition config file.
+    pub fn transitions<S: ToString>(mut self, path: S) -> Self {
+        self.transitions = Some(path.to_string());
+        self
+    }
+    /// Checks all the config files and returns every problem that was found.
+    pub fn check(&self) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        let mut names = Names::new();
+        if let Some(ref path) = self.gui {
+            if let Some(text) = read_config(path, &mut issues) {
+                if let Err(e) = toml::from_str::<HashMap<String, TextBoxBuilder>>(&text) {
+                    issues.push(Issue::Config(path.to_string(), e.to_string()));
+                }
+            }
+        }
+        if let Some(ref path) = self.characters {
+            let characters = read_config(path, &mut issues)
+                .and_then(|text| config_result(path, parse_characters_from_str(&text), &mut issues));
+            // Sorted so that the issues are always in the same order.
+            let characters: BTreeMap<_, _> = characters.unwrap_or_default().into_iter().collect();
+            for (name, character) in characters {
+                let states: BTreeMap<_, _> = character.state_map.iter().collect();
+                for (state, image) in states {
+                    check_file(path, &format!("state '{}' of character '{}'", state, name), image, &mut issues);
+                }
+                if !character.state_map.contains_key(&character.default) {
+                    issues.push(Issue::Config(path.to_string(),
+                        format!("character '{}' doesn't have its default state '{}'", name, character.default)));
+                }
+                names.characters.insert(name, character.state_map.keys().cloned().collect());
+            }
+        }
+        if let Some(ref path) = self.backgrounds {
+            let backgrounds = read_config(path, &mut issues)
+                .and_then(|text| config_result(path, parse_backgrounds_from_str(&text), &mut issues));
+            let backgrounds: BTreeMap<_, _> = backgrounds.unwrap_or_default().into_iter().collect();
+            for (name, image) in backgrounds {
+                check_file(path, &format!("background '{}'", name), &image, &mut issues);
+                names.backgrounds.insert(name);
+            }
+        }
+        if let Some(ref path) = self.input {
+            if let Some(text) = read_config(path, &mut issues) {
+                config_result(path, load_input_from_str(&text), &mut issues);
+            }
+        }
+        if let Some(ref path) = self.music {
+            let music = read_config(path, &mut issues)
+                .and_then(|text| config_result(path, parse_music_from_str(&text), &mut issues));
+            let music = music.unwrap_or_default();
+            for (name, file) in music.music.into_iter().collect::<BTreeMap<_, _>>() {
+                check_file(path, &format!("music '{}'", name), &file, &mut issues);
+                names.music.insert(name);
+            }
+            for (name, file) in music.sfx.into_iter().collect::<BTreeMap<_, _>>() {
+                check_file(path, &format!("sound effect '{}'", name), &file, &mut issues);
+                names.sfx.insert(name);
+            }
+            for (name, file) in music.voice.into_iter().collect::<BTreeMap<_, _>>() {
+                check_file(path, &format!("voice line '{}'", name), &file, &mut issues);
+                names.voice.insert(name);
+            }
+        }
+        if let Some(ref path) = self.transitions {
+            let anims = read_config(path, &mut issues)
+                .and_then(|text| config_result(path, load_transitions_from_str(&text), &mut issues));
+            if let Some(anims) = anims {
+                names.transitions.extend(anims.char_trans.keys().cloned());
+            }
+        }
+        if let Some(ref path) = self.scripts {
+            if let Some(text) = read_config(path, &mut issues) {
+                check_scripts(path, &text, &names, &mut issues);
+            }
+        }
+        issues
+    }
+}
+
+/// Parses every script in the Script config file and checks them against the names.
+fn check_scripts(path: &str, text: &str, names: &Names, issues: &mut Vec<Issue>) {
+    let config: BTreeMap<String, String> = match toml::from_str(text) {
+        Ok(e) => e,
+        Err(e) => return issues.push(Issue::Config(path.to_string(), e.to_string())),
+    };
+    let mut story = Script::new();
+    let mut files = HashSet::new();
+    let mut default = None;
+    for (k, v) in config.iter() {
+        match k.to_lowercase().as_str() {
+            "default" => default = Some(v.to_string()),
+            key => {
+                if let Some(buffer) = read_file(path, &format!("script '{}'", key), v, issues) {
+                    match translate_with_source(&buffer) {
+                        Ok((map, mut source)) => {
+                            source.file = Some(v.to_string());
+                            story.load_script(key.to_string(), map);
+                            story.sources.insert(key.to_string(), source);
+                        },
+                        Err(e) => issues.push(match e.with_file(v) {
+                            ScriptImportError::Parse(e) => Issue::Parse(e),
+                            ScriptImportError::Io(e) => Issue::Config(v.to_string(), e.to_string()),
+                        }),
+                    }
+                }
+                files.insert(key.to_string());
+            },
+        }
+    }
+    if let Some(default) = default {
+        if !files.contains(&default) {
+            issues.push(Issue::Config(path.to_string(), format!("the default script '{}' doesn't exist", default)));
+        }
+    }
+    issues.extend(story.validate(names).into_iter().map(Issue::Script));
+}
+
+/// Reads a config file, adding an issue if it can't be read.
+fn read_config(path: &str, issues: &mut Vec<Issue>) -> Option<String> {
+    let mut buffer = String::new();
+    match File::open(path).and_then(|mut f| f.read_to_string(&mut buffer)) {
+        Ok(_) => Some(buffer),
+        Err(e) => {
+            issues.push(Issue::Config(path.to_string(), e.to_string()));
+            None
+        },
+    }
+}
+
+/// Reads a file that a config file points to, adding an issue to the config file if it can't be read.
+fn read_file(config: &str, what: &str, path: &str, issues: &mut Vec<Issue>) -> Option<String> {
+    let mut buffer = String::new();
+    match File::open(path).and_then(|mut f| f.read_to_string(&mut buffer)) {
+        Ok(_) => Some(buffer),
+        Err(e) => {
+            issues.push(Issue::Config(config.to_string(), format!("{} can't be read from '{}': {}", what, path, e)));
+            None
+        },
+    }
+}
+
+/// Adds an issue if a file that a config file points to doesn't exist.
+fn check_file(config: &str, what: &str, path: &str, issues: &mut Vec<Issue>) {
+    if !Path::new(path).is_file() {
+        issues.push(Issue::Config(config.to_string(), format!("the file of {} doesn't exist: '{}'", what, path)));
+    }
+}
+
+/// Adds an issue if a config file couldn't be parsed.
+fn config_result<T>(path: &str, result: Result<T, ConfigImportError>, issues: &mut Vec<Issue>) -> Option<T> {
+    match result {
+        Ok(e) => Some(e),
+        Err(e) => {
+            issues.push(Issue::Config(path.to_string(), e.to_string()));
+            None
+        },
+    }
+}
+
+#[test]
+fn test_check() {
+    use std::{
+        env,
+        fs,
+        io::Write,
+    };
+    let dir = env::temp_dir().join("gobu_check_test");
+    fs::create_dir_all(&dir).unwrap();
+    let file = |name: &str, text: &str| {
+        let path = dir.join(name);
+        File::create(&path).unwrap().write_all(text.as_bytes()).unwrap();
+        path.to_str().unwrap().replace('\\', "/")
+    };
+    let image = file("aiko.png", "");
+    let characters = file("characters.toml", &format!("[Aiko]\nhappy = \"{}\"\nsad = \"{}/missing.png\"\n",
+        image, dir.to_str().unwrap().replace('\\', "/")));
+    let main = file("main.txt", ":start\n    SPAWN 'Aiko'\n    SHOW 'Aiko' ~ 'angry'\n    STAGE 'park'\n");
+    let broken = file("broken.txt", ":start\n    SHOW\n");
+    let scripts = file("scripts.toml", &format!("main = \"{}\"\nbroken = \"{}\"\n", main, broken));
+    let issues = Project::new()
+        .characters(characters.as_str())
+        .scripts(scripts)
+        .backgrounds(dir.join("missing.toml").to_str().unwrap())
+        .check();
+    let texts: Vec<String> = issues.iter().map(|i| i.to_string()).collect();
+    assert_eq!(issues.len(), 5);
+    assert_eq!(issues.iter().filter(|i| match **i { Issue::Parse(_) => true, _ => false }).count(), 1);
+    assert_eq!(issues.iter().filter(|i| match **i { Issue::Script(..) => true, _ => false }).count(), 2);
+    assert!(texts.contains(&format!("{}: the file of state 'sad' of character 'Aiko' doesn't exist: '{}/missing.png'",
+        characters, dir.to_str().unwrap().replace('\\', "/"))));
+    assert!(texts.contains(&format!("{}:2:5: expected SHOW 'Entity' [~ 'State'] [with 'Transition'] (in anchor 'start')\n  |\n2 |     SHOW\n  |     ^",
+        broken)));
+    assert!(texts.contains(&format!("{}:3: entity 'Aiko' doesn't have a state called 'angry' (in anchor 'start')", main)));
+}
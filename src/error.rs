@@ -3,11 +3,16 @@
 //! These crates are all just enums that have all the possible different errors from
 //! other crates.
 
-use std::io::Error as IoError;
-use toml::de::Error as TomlError;
-use nom::Err as NomErr;
-use nom::ErrorKind;
+use std::{
+    fmt,
+    io::Error as IoError,
+};
+use toml::{
+    de::Error as TomlError,
+    ser::Error as TomlSerError,
+};
 use rodio::decoder::DecoderError;
+use bincode::Error as BincodeError;
 
 /// An error created when an error occurs reading the TOML files.
 #[derive(Debug)]
@@ -27,6 +32,53 @@ impl From<TomlError> for ConfigImportError {
         ConfigImportError::Toml(err)
     }
 }
+impl fmt::Display for ConfigImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConfigImportError::Io(ref e) => write!(f, "{}", e),
+            ConfigImportError::Toml(ref e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Where an error is in a script and what was expected there.
+#[derive(Debug, Clone)]
+pub struct ScriptParseError {
+    /// The file that the script was read from.
+    /// ``None`` if the script wasn't read from a file.
+    pub file: Option<String>,
+    /// The anchor that the error is in.
+    pub anchor: String,
+    /// The line that the error is on. (Starts from 1)
+    pub line: usize,
+    /// The column that the error is on. (Starts from 1)
+    pub column: usize,
+    /// The text of the line that the error is on.
+    pub text: String,
+    /// A hint about what was expected.
+    pub expected: String,
+}
+impl ScriptParseError {
+    /// Sets the file that the script was read from.
+    pub fn with_file<S: ToString>(mut self, file: S) -> Self {
+        self.file = Some(file.to_string());
+        self
+    }
+}
+impl fmt::Display for ScriptParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let file = match self.file {
+            Some(ref e) => e.as_str(),
+            None => "<script>",
+        };
+        let number = self.line.to_string();
+        let padding = " ".repeat(number.len());
+        writeln!(f, "{}:{}:{}: {} (in anchor '{}')", file, self.line, self.column, self.expected, self.anchor)?;
+        writeln!(f, "{} |", padding)?;
+        writeln!(f, "{} | {}", number, self.text)?;
+        write!(f, "{} | {}^", padding, " ".repeat(self.column.saturating_sub(1)))
+    }
+}
 
 /// An error created when an error occurs reading the script file.
 #[derive(Debug)]
@@ -34,16 +86,25 @@ pub enum ScriptImportError {
     /// Error caused by trying to opening the file.
     Io(IoError),
     /// Error caused by trying to parse the script.
-    Nom(ErrorKind)
+    Parse(ScriptParseError),
+}
+impl ScriptImportError {
+    /// Sets the file that the script was read from if this is a [`ScriptParseError`].
+    pub fn with_file<S: ToString>(self, file: S) -> Self {
+        match self {
+            ScriptImportError::Parse(e) => ScriptImportError::Parse(e.with_file(file)),
+            e => e,
+        }
+    }
 }
 impl From<IoError> for ScriptImportError {
     fn from(err: IoError) -> Self {
         ScriptImportError::Io(err)
     }
 }
-impl<I: AsRef<str>> From<NomErr<I>> for ScriptImportError {
-    fn from(err: NomErr<I>) -> Self {
-        ScriptImportError::Nom(err.into_error_kind())
+impl From<ScriptParseError> for ScriptImportError {
+    fn from(err: ScriptParseError) -> Self {
+        ScriptImportError::Parse(err)
     }
 }
 
@@ -53,7 +114,7 @@ pub enum ScriptConfigImportError {
     /// Error caused by trying to opening the file.
     Io(IoError),
     /// Error caused by trying to parse the script.
-    Nom(ErrorKind),
+    Parse(ScriptParseError),
     /// Error caused by reading the TOML file.
     Toml(TomlError),
 }
@@ -69,7 +130,7 @@ impl From<ScriptImportError> for ScriptConfigImportError {
     fn from(err: ScriptImportError) -> ScriptConfigImportError {
         match err {
             ScriptImportError::Io(e) => ScriptConfigImportError::Io(e),
-            ScriptImportError::Nom(e) => ScriptConfigImportError::Nom(e),
+            ScriptImportError::Parse(e) => ScriptConfigImportError::Parse(e),
         }
     }
 }
@@ -101,12 +162,22 @@ pub enum GameBuildError {
     Music(ConfigImportError),
     /// Error importing the Transition file.
     Transition(ConfigImportError),
+    /// Error reading the script bundle.
+    Bundle(BundleError),
+    /// The script that the player was on was removed while hot reloading
+    /// so the story was moved to the start of the default script.
+    ScriptRemoved(String),
 }
 impl From<ScriptConfigImportError> for GameBuildError {
     fn from(err: ScriptConfigImportError) -> GameBuildError {
         GameBuildError::Story(err)
     }
 }
+impl From<BundleError> for GameBuildError {
+    fn from(err: BundleError) -> GameBuildError {
+        GameBuildError::Bundle(err)
+    }
+}
 
 /// An error caused by trying to play music.
 #[derive(Debug)]
@@ -127,4 +198,76 @@ impl From<IoError> for MusicError {
     fn from(err: IoError) -> MusicError {
         MusicError::Io(err)
     }
-}
\ No newline at end of file
+}
+
+/// An error caused by saving or loading the game.
+#[derive(Debug)]
+pub enum SaveError {
+    /// Error reading or writing the save file.
+    Io(IoError),
+    /// Error reading the save file.
+    Toml(TomlError),
+    /// Error turning the save into TOML.
+    Serialize(TomlSerError),
+    /// The save was made using a different version of the save format.
+    Version(u32),
+}
+impl From<IoError> for SaveError {
+    fn from(err: IoError) -> SaveError {
+        SaveError::Io(err)
+    }
+}
+impl From<TomlError> for SaveError {
+    fn from(err: TomlError) -> SaveError {
+        SaveError::Toml(err)
+    }
+}
+impl From<TomlSerError> for SaveError {
+    fn from(err: TomlSerError) -> SaveError {
+        SaveError::Serialize(err)
+    }
+}
+
+/// An error caused by compiling or loading a script bundle.
+#[derive(Debug)]
+pub enum BundleError {
+    /// Error reading or writing the bundle.
+    Io(IoError),
+    /// Error turning the scripts into or out of the binary format.
+    Bincode(BincodeError),
+    /// Error reading the scripts that are being compiled.
+    Scripts(ScriptConfigImportError),
+    /// The file isn't a script bundle.
+    NotABundle,
+    /// The bundle was made using a different version of the bundle format.
+    Version(u32),
+}
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BundleError::Io(ref e) => write!(f, "{}", e),
+            BundleError::Bincode(ref e) => write!(f, "{}", e),
+            BundleError::Scripts(ScriptConfigImportError::Io(ref e)) => write!(f, "{}", e),
+            BundleError::Scripts(ScriptConfigImportError::Parse(ref e)) => write!(f, "{}", e),
+            BundleError::Scripts(ScriptConfigImportError::Toml(ref e)) => write!(f, "{}", e),
+            BundleError::NotABundle => write!(f, "not a script bundle"),
+            BundleError::Version(v) => write!(f, "the bundle was made with version {} of the format (expected {})",
+                v, ::script::bundle::BUNDLE_VERSION),
+        }
+    }
+}
+impl From<IoError> for BundleError {
+    fn from(err: IoError) -> BundleError {
+        BundleError::Io(err)
+    }
+}
+impl From<BincodeError> for BundleError {
+    fn from(err: BincodeError) -> BundleError {
+        BundleError::Bincode(err)
+    }
+}
+impl From<ScriptConfigImportError> for BundleError {
+    fn from(err: ScriptConfigImportError) -> BundleError {
+        BundleError::Scripts(err)
+    }
+}
@@ -20,14 +20,19 @@ use ::{
 use std::{
     collections::HashMap,
     sync::Arc,
-    path::Path,
+    path::{
+        Path,
+        PathBuf,
+    },
 };
+use indexmap::IndexMap;
 
 use super::{
     gui::{
         Ui,
         Grid,
         TextBox,
+        ChoiceMenu,
     },
     images::BackgroundImage,
     Rect, Pos,
@@ -63,6 +68,14 @@ use super::{
         Music,
     },
     animation::Animation,
+    rollback::{
+        History,
+        DEFAULT_HISTORY_LIMIT,
+    },
+    mode::PlayMode,
+    read::ReadLines,
+    reload::HotReload,
+    settings::Settings,
 };
 
 /// The game
@@ -75,8 +88,9 @@ pub struct Game {
     pub background: BackgroundImage,
     /// A HashMap of all the backgrounds that the background can be set to
     pub backgrounds: HashMap<String, Arc<G2dTexture>>,
-    /// A HashMap of all the characters on the screen / in the world
-    pub stage: HashMap<String, CharacterEntity>,
+    /// All the characters on the screen / in the world in the order that they are drawn.
+    /// Characters that are spawned later are drawn in front.
+    pub stage: IndexMap<String, CharacterEntity>,
     /// A HashMap of all the characters that are or can be put onto the stage
     pub characters: HashMap<String, Character>,
     /// Holds a collection of all the scripts and also the current script
@@ -90,6 +104,21 @@ pub struct Game {
     /// The animations that are displayed in the game like CharacterTransitions.
     pub anims: Animation,
     garbage: Vec<String>,
+    /// The last known position of the mouse cursor.
+    cursor: Pos,
+    /// The directory that the save slots are kept in. Set to ``./saves`` by default.
+    pub save_dir: PathBuf,
+    /// The snapshots of the lines of dialogue that can be gone back to.
+    pub history: History,
+    /// Whether auto mode or skip mode is on.
+    pub mode: PlayMode,
+    /// The lines of dialogue that the player has ever read.
+    pub read: ReadLines,
+    /// The config files that are reloaded when they change.
+    /// ``None`` unless hot reloading has been turned on.
+    pub reload: Option<HotReload>,
+    /// The settings that the player has chosen, like the volume.
+    pub settings: Settings,
 }
 
 impl Game {
@@ -109,7 +138,7 @@ impl Game {
             ui: Ui::new(size),
             background: BackgroundImage::new(size),
             backgrounds: HashMap::new(),
-            stage: HashMap::new(),
+            stage: IndexMap::new(),
             characters: HashMap::new(),
             story: Script::new(),
             grid: Grid::new(1, 1, size),
@@ -117,9 +146,18 @@ impl Game {
             music: None,
             anims: Animation::new(),
             garbage: vec![],
+            cursor: Pos::new(0., 0.),
+            save_dir: PathBuf::from("./saves"),
+            history: History::new(DEFAULT_HISTORY_LIMIT),
+            mode: PlayMode::new(),
+            read: ReadLines::new(),
+            reload: None,
+            settings: Settings::new(),
         }
     }
-    /// Handles a piston event. Currently only going forward in the story is supported.
+    /// Handles a piston event.
+    /// Currently going forward and back in the story, picking the option of a choice
+    /// and scrolling through the backlog are supported.
     pub fn handle_event(&mut self, event: &Event) {
         use self::Event::{
             Input,
@@ -128,24 +166,97 @@ impl Game {
         match *event {
             Input(ref i) => {
                 use piston_window::{
-                    Input::Button,
+                    Input::{Button, Move},
                     ButtonState::Press,
+                    Motion::{
+                        MouseCursor,
+                        MouseScroll,
+                    },
                 };
                 match i {
                     Button(args) if args.state == Press => {
                         use input::GameEvent;
                         let event = self.input.handle_event(&args.button);
-                        if let Some(e) = event {
+                        // The story is paused while the backlog is open.
+                        if self.ui.backlog.open {
+                            match event {
+                                Some(GameEvent::Up) => self.ui.backlog.scroll_up(1),
+                                Some(GameEvent::Down) => self.ui.backlog.scroll_down(1),
+                                Some(GameEvent::Continue) | Some(GameEvent::Back) | Some(GameEvent::Backlog) => {
+                                    self.ui.backlog.hide();
+                                },
+                                _ => {},
+                            }
+                        } else if let Some(e) = event {
                             match e {
                                 GameEvent::Continue => {
-                                    for i in self.stage.values_mut() {
-                                        i.finish()
+                                    // Pressing continue takes back control from skip mode.
+                                    self.mode.skip = false;
+                                    if self.story.is_choosing() {
+                                        // A mouse click only picks an option if the cursor is over it.
+                                        let index = match args.button {
+                                            ::piston_window::Button::Mouse(_) => self.ui.choices.option_at(self.cursor),
+                                            _ => Some(self.ui.choices.selected),
+                                        };
+                                        if let Some(index) = index {
+                                            self.select_choice(index);
+                                        }
+                                    } else if self.ui.is_revealing() {
+                                        // The first press shows the rest of the text.
+                                        self.ui.finish_reveal();
+                                    } else {
+                                        for i in self.stage.values_mut() {
+                                            i.finish()
+                                        }
+                                        self.next_step();
                                     }
-                                    self.next_step();
+                                },
+                                GameEvent::Up => {
+                                    self.ui.choices.select_previous();
+                                },
+                                GameEvent::Down => {
+                                    self.ui.choices.select_next();
+                                },
+                                GameEvent::Back => {
+                                    self.rollback();
+                                },
+                                GameEvent::Backlog => {
+                                    self.ui.backlog.show();
+                                },
+                                GameEvent::Auto => {
+                                    self.toggle_auto();
+                                },
+                                GameEvent::Skip => {
+                                    self.toggle_skip();
+                                },
+                                GameEvent::ReplayVoice => {
+                                    self.replay_voice();
                                 },
                             }
                         }
                     },
+                    Move(MouseCursor(x, y)) => {
+                        self.cursor = Pos::new(*x, *y);
+                        if let Some(index) = self.ui.choices.option_at(self.cursor) {
+                            self.ui.choices.select(index);
+                        }
+                    },
+                    Move(MouseScroll(_, y)) => {
+                        // Scrolling up opens the backlog and scrolling down past the latest line closes it.
+                        if *y > 0. {
+                            if self.ui.backlog.open {
+                                self.ui.backlog.scroll_up(1);
+                            } else {
+                                self.ui.backlog.show();
+                            }
+                        } else if *y < 0. && self.ui.backlog.open {
+                            if self.ui.backlog.scroll == 0 {
+                                self.ui.backlog.hide();
+                            } else {
+                                self.ui.backlog.scroll_down(1);
+                            }
+                        }
+                    },
                     _ => {},
                 }
             },
@@ -163,10 +274,27 @@ impl Game {
                         }
                         if trash_present {
                             for i in self.garbage.iter() {
-                                self.stage.remove(i);
+                                // Shifting keeps the order that the rest are drawn in.
+                                self.stage.shift_remove(i);
                             }
                             self.garbage.clear();
                         }
+                        self.ui.update(args.dt);
+                        // The story doesn't continue on its own while the backlog is being read
+                        // or while the text is still being revealed.
+                        let paused = self.ui.backlog.open || self.ui.is_revealing();
+                        if let Some(time) = self.story.timer.filter(|_| !paused) {
+                            let time = time - args.dt;
+                            if time <= 0. {
+                                self.next_step();
+                            } else {
+                                self.story.timer = Some(time);
+                            }
+                        }
+                        self.update_modes(args.dt);
+                        if let Some(ref mut m) = self.music {
+                            m.update(args.dt);
+                        }
                     },
                     _ => {},
                 }
@@ -230,6 +358,7 @@ impl Game {
     pub fn set_background(&mut self, name: &String) {
         if let Some(bg) = self.backgrounds.get(name) {
             self.background.set_texture(bg.clone());
+            self.background.name = Some(name.to_string());
         }
     }
     /// Change the state of a [`CharacterEntity`] on the stage.
@@ -237,8 +366,9 @@ impl Game {
     pub fn change_entity_state(&mut self, name: &String, state: &String) -> bool {
         if let Some(entity) = self.stage.get_mut(name) {
             if let Some(chara) = self.characters.get(&entity.name) {
-                if let Some(state) = chara.state_map.get(state) {
-                    entity.texture = state.clone();
+                if let Some(texture) = chara.state_map.get(state) {
+                    entity.texture = texture.clone();
+                    entity.state = state.to_string();
                     true
                 } else {false}
             } else {false}
@@ -278,6 +408,7 @@ impl Game {
     pub fn enable_music(&mut self) -> Result<&mut Music, MusicError> {
         self.music = Some(Music::new()?);
         if let Some(ref mut e) = self.music {
+            e.set_volumes(self.settings.volume);
             Ok(e)
         } else {
             Err(MusicError::NoDefaultOutputDeviceFound)
@@ -348,6 +479,15 @@ impl Game {
         if let Some(e)= gui.get("speakerbox") {
             self.set_speaker_box(e.clone());
         }
+        if let Some(e) = gui.get("choicebox") {
+            self.ui.choices = ChoiceMenu::from_template(e.clone());
+        }
+        if let Some(e) = gui.get("backlogspeaker") {
+            self.ui.backlog.speaker_template = e.clone();
+        }
+        if let Some(e) = gui.get("backlogtext") {
+            self.ui.backlog.text_template = e.clone();
+        }
     }
     /// Load the backgrounds from a TOML file.
     pub fn load_backgrounds_from_file<P: AsRef<Path>>(&mut self, path: P, factory: &mut GfxFactory)
@@ -380,10 +520,13 @@ impl Game {
         let scripts = load_scripts_from_str(text)?;
         Ok(self.load_scripts(scripts))
     }
-    fn load_scripts(&mut self, file: ScriptsFromFile) {
+    /// Load scripts that have already been read, like the ones from a bundle.
+    pub fn load_scripts(&mut self, file: ScriptsFromFile) {
         self.story.load_scripts(file.map.clone());
+        self.story.sources.extend(file.sources);
         if let Some(e) = file.default {
             self.story.set_script(&e, None);
+            self.story.default = Some(e);
         }
     }
     /// Load transitions from a TOML file.
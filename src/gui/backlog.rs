@@ -0,0 +1,246 @@
+//! A screen that lists the lines of dialogue that have already been shown.
+//!
+//! Every line is drawn as a row with the name of the speaker in its own [`TextBox`]
+//! on the left and the dialogue on the right.
+
+use piston_window::{
+    G2d,
+    Rectangle,
+    context::Context,
+    draw_state::DrawState,
+};
+use coord::vec2::Vec2;
+use std::collections::VecDeque;
+#[cfg(not(feature = "gfx_glyph_text"))]
+use ::piston_window::Glyphs;
+#[cfg(feature = "gfx_glyph_text")]
+use ::{
+    gfx_glyph::GlyphBrush,
+    gfx_device_gl::Resources,
+    piston_window::GfxFactory,
+};
+use super::{
+    Rect,
+    TextBox,
+};
+use Pos;
+
+/// How many lines are kept by default.
+pub const DEFAULT_BACKLOG_LIMIT: usize = 200;
+
+/// A struct that keeps the lines of dialogue that have been shown and draws them when it is open.
+pub struct Backlog {
+    /// The lines that have been shown with the oldest at the front.
+    /// (Speaker, Text)
+    pub lines: VecDeque<(String, String)>,
+    /// The most lines that are kept. The oldest ones are removed first.
+    pub limit: usize,
+    /// How many lines have been added, including the ones that were removed because of the limit.
+    pub shown: usize,
+    /// Whether the backlog is being shown.
+    pub open: bool,
+    /// How many lines the backlog has been scrolled up from the latest line.
+    pub scroll: usize,
+    /// How many lines are shown at once.
+    pub rows: usize,
+    /// The area that the backlog is drawn in.
+    pub area: Rect,
+    /// The colour of the rectangle that is drawn behind the backlog.
+    pub colour: [f32; 4],
+    /// The textbox that the name of the speaker of every line is copied from.
+    pub speaker_template: TextBox,
+    /// The textbox that the text of every line is copied from.
+    pub text_template: TextBox,
+    /// The textboxes of the lines that are currently shown.
+    /// (Speaker, Text)
+    entries: Vec<(TextBox, TextBox)>,
+}
+impl Backlog {
+    /// Create a new [`Backlog`] struct that covers most of the screen.
+    pub fn new(canvas: Rect) -> Backlog {
+        let area = Rect {
+            pos: canvas.pos + vec2![0.05, 0.05] * canvas.size,
+            size: vec2![0.9, 0.9] * canvas.size,
+        };
+        let mut template = TextBox::new(area);
+        template.rect = Rectangle::new([0.; 4]);
+        Backlog {
+            lines: VecDeque::new(),
+            limit: DEFAULT_BACKLOG_LIMIT,
+            shown: 0,
+            open: false,
+            scroll: 0,
+            rows: 6,
+            area,
+            colour: [0., 0., 0., 0.85],
+            speaker_template: template.clone(),
+            text_template: template,
+            entries: Vec::new(),
+        }
+    }
+    /// Adds a line to the backlog.
+    pub fn push(&mut self, speaker: String, text: String) {
+        self.shown += 1;
+        if self.limit == 0 {
+            return
+        }
+        self.lines.push_back((speaker, text));
+        while self.lines.len() > self.limit {
+            self.lines.pop_front();
+        }
+        if self.open {
+            self.layout();
+        }
+    }
+    /// Removes the lines that were added after the first ``shown`` lines.
+    /// This is used when going back to an earlier point of the story.
+    pub fn truncate(&mut self, shown: usize) {
+        let extra = self.shown.saturating_sub(shown).min(self.lines.len());
+        for _ in 0..extra {
+            self.lines.pop_back();
+        }
+        self.shown = shown;
+        self.scroll = self.scroll.min(self.lines.len().saturating_sub(self.rows));
+        if self.open {
+            self.layout();
+        }
+    }
+    /// Removes all the lines.
+    pub fn clear(&mut self) {
+        self.shown = 0;
+        self.lines.clear();
+        self.scroll = 0;
+        self.entries.clear();
+    }
+    /// Shows the backlog scrolled down to the latest line.
+    pub fn show(&mut self) {
+        self.open = true;
+        self.scroll = 0;
+        self.layout();
+    }
+    /// Stops showing the backlog.
+    pub fn hide(&mut self) {
+        self.open = false;
+        self.entries.clear();
+    }
+    /// Shows the backlog if it is hidden and hides it if it is shown.
+    pub fn toggle(&mut self) {
+        if self.open {
+            self.hide();
+        } else {
+            self.show();
+        }
+    }
+    /// Scrolls up to older lines.
+    pub fn scroll_up(&mut self, lines: usize) {
+        let max = self.lines.len().saturating_sub(self.rows);
+        self.scroll = (self.scroll + lines).min(max);
+        self.layout();
+    }
+    /// Scrolls down to newer lines.
+    pub fn scroll_down(&mut self, lines: usize) {
+        self.scroll = self.scroll.saturating_sub(lines);
+        self.layout();
+    }
+    /// The lines that are shown with the oldest first.
+    pub fn visible_lines(&self) -> Vec<&(String, String)> {
+        let end = self.lines.len().saturating_sub(self.scroll);
+        let start = end.saturating_sub(self.rows);
+        self.lines.iter()
+            .skip(start)
+            .take(end - start)
+            .collect()
+    }
+    /// Creates the textboxes of the lines that are shown.
+    fn layout(&mut self) {
+        let height = self.area.size.y / self.rows.max(1) as f64;
+        let speaker_width = self.area.size.x * 0.2;
+        let entries = self.visible_lines()
+            .into_iter()
+            .enumerate()
+            .map(|(i, &(ref speaker, ref text))| {
+                let y = self.area.pos.y + height * i as f64;
+                let mut speaker_box = self.speaker_template.clone();
+                speaker_box.outer = Rect {
+                    pos: Pos::new(self.area.pos.x, y),
+                    size: vec2![speaker_width, height],
+                };
+                speaker_box.calculate_inner();
+                speaker_box.chars_per_second = None;
+                speaker_box.set_text(speaker.to_string());
+                let mut text_box = self.text_template.clone();
+                text_box.outer = Rect {
+                    pos: Pos::new(self.area.pos.x + speaker_width, y),
+                    size: vec2![self.area.size.x - speaker_width, height],
+                };
+                text_box.calculate_inner();
+                text_box.chars_per_second = None;
+                text_box.set_text(text.to_string());
+                (speaker_box, text_box)
+            })
+            .collect();
+        self.entries = entries;
+    }
+    /// Draws the backlog if it is open.
+    #[cfg(not(feature = "gfx_glyph_text"))]
+    pub fn draw(&mut self, c: Context, g: &mut G2d, glyph_cache: &mut Glyphs) {
+        if !self.open {
+            return
+        }
+        Rectangle::new(self.colour)
+            .draw(self.area.to_slice(), &DrawState::default(), c.transform, g);
+        for &mut (ref mut speaker, ref mut text) in self.entries.iter_mut() {
+            speaker.draw(c, g, glyph_cache);
+            text.draw(c, g, glyph_cache);
+        }
+    }
+    /// Draws the backlog if it is open.
+    #[cfg(feature = "gfx_glyph_text")]
+    pub fn draw(&self, c: Context, g: &mut G2d) {
+        if !self.open {
+            return
+        }
+        Rectangle::new(self.colour)
+            .draw(self.area.to_slice(), &DrawState::default(), c.transform, g);
+        for &(ref speaker, ref text) in self.entries.iter() {
+            speaker.draw(c, g);
+            text.draw(c, g);
+        }
+    }
+    /// Queues the text of the lines into the brush if it is open.
+    #[cfg(feature = "gfx_glyph_text")]
+    pub fn draw_text(&self, brush: &mut GlyphBrush<Resources, GfxFactory>) {
+        if !self.open {
+            return
+        }
+        for &(ref speaker, ref text) in self.entries.iter() {
+            speaker.draw_text(brush);
+            text.draw_text(brush);
+        }
+    }
+}
+
+#[test]
+fn test_backlog_scroll() {
+    let mut backlog = Backlog::new(Rect {
+        pos: Pos::new(0., 0.),
+        size: vec2![800., 600.],
+    });
+    backlog.limit = 10;
+    backlog.rows = 3;
+    for i in 0..12 {
+        backlog.push("Aiko".to_string(), format!("Line {}", i));
+    }
+    assert_eq!(backlog.lines.len(), 10);
+    assert_eq!(backlog.visible_lines()[0].1, "Line 9");
+    backlog.scroll_up(100);
+    assert_eq!(backlog.scroll, 7);
+    assert_eq!(backlog.visible_lines()[0].1, "Line 2");
+    backlog.scroll_down(1);
+    assert_eq!(backlog.visible_lines()[2].1, "Line 5");
+    backlog.truncate(9);
+    assert_eq!(backlog.lines.len(), 7);
+    assert_eq!(backlog.lines.back().unwrap().1, "Line 8");
+    backlog.push("Aiko".to_string(), "Line 9".to_string());
+    assert_eq!(backlog.shown, 10);
+}
@@ -0,0 +1,170 @@
+//! A menu that shows the options of a choice the player has to make.
+//!
+//! Every option is drawn as its own [`TextBox`] which are stacked on top of each other.
+
+use piston_window::{
+    G2d,
+    Rectangle,
+    context::Context,
+};
+use coord::vec2::Vec2;
+#[cfg(not(feature = "gfx_glyph_text"))]
+use ::piston_window::Glyphs;
+#[cfg(feature = "gfx_glyph_text")]
+use ::{
+    gfx_glyph::GlyphBrush,
+    gfx_device_gl::Resources,
+    piston_window::GfxFactory,
+};
+use super::{
+    Rect,
+    TextBox,
+};
+use Pos;
+
+/// A struct that draws the options of a choice and keeps track of which one is selected.
+pub struct ChoiceMenu {
+    /// The textbox that every option is copied from.
+    /// Its rectangle is used as the position and size of the first option.
+    pub template: TextBox,
+    /// The textboxes of the options that are currently shown.
+    pub options: Vec<TextBox>,
+    /// The index of the option that is currently selected.
+    pub selected: usize,
+    /// The colour of the rectangle of an option that isn't selected.
+    pub colour: [f32; 4],
+    /// The colour of the rectangle of the selected option.
+    pub selected_colour: [f32; 4],
+    /// The vertical gap between the options as a percentage of the height of an option.
+    pub gap: f64,
+}
+impl ChoiceMenu {
+    /// Create a new [`ChoiceMenu`] struct.
+    pub fn new(canvas: Rect) -> ChoiceMenu {
+        let template = TextBox::new(
+            Rect {
+                pos: canvas.pos + vec2![0.2, 0.2] * canvas.size,
+                size: vec2![0.6, 0.08] * canvas.size,
+            }
+        );
+        ChoiceMenu::from_template(template)
+    }
+    /// Create a new [`ChoiceMenu`] where every option looks like the ``template``.
+    pub fn from_template(template: TextBox) -> ChoiceMenu {
+        ChoiceMenu {
+            colour: template.rect.color,
+            template,
+            options: Vec::new(),
+            selected: 0,
+            selected_colour: [0.2, 0.2, 0.4, 1.],
+            gap: 0.25,
+        }
+    }
+    /// Sets the options that are shown. The first option is selected.
+    pub fn set_options(&mut self, options: Vec<String>) {
+        let size = self.template.outer.size;
+        let start = self.template.outer.pos;
+        self.options = options.into_iter()
+            .enumerate()
+            .map(|(i, text)| {
+                let mut text_box = self.template.clone();
+                text_box.outer = Rect {
+                    pos: Pos::new(start.x, start.y + size.y * (1. + self.gap) * i as f64),
+                    size,
+                };
+                text_box.calculate_inner();
+                text_box.set_text(text);
+                text_box
+            })
+            .collect();
+        self.select(0);
+    }
+    /// Removes all the options.
+    pub fn clear(&mut self) {
+        self.options.clear();
+        self.selected = 0;
+    }
+    /// Whether there are options being shown or not.
+    pub fn is_active(&self) -> bool {
+        !self.options.is_empty()
+    }
+    /// Selects the option at ``index`` if it exists.
+    pub fn select(&mut self, index: usize) {
+        if index < self.options.len() {
+            self.selected = index;
+        }
+        for (i, option) in self.options.iter_mut().enumerate() {
+            let colour = if i == self.selected { self.selected_colour } else { self.colour };
+            option.rect = Rectangle::new(colour);
+        }
+    }
+    /// Selects the option above the current one, wrapping around to the bottom.
+    pub fn select_previous(&mut self) {
+        if self.is_active() {
+            let len = self.options.len();
+            let index = (self.selected + len - 1) % len;
+            self.select(index);
+        }
+    }
+    /// Selects the option below the current one, wrapping around to the top.
+    pub fn select_next(&mut self) {
+        if self.is_active() {
+            let index = (self.selected + 1) % self.options.len();
+            self.select(index);
+        }
+    }
+    /// Returns the index of the option that is at the position (in pixels) if there is one.
+    pub fn option_at(&self, pos: Pos) -> Option<usize> {
+        self.options.iter()
+            .position(|option| {
+                let rect = option.outer;
+                pos.x >= rect.pos.x && pos.x <= rect.pos.x + rect.size.x
+                    && pos.y >= rect.pos.y && pos.y <= rect.pos.y + rect.size.y
+            })
+    }
+    /// Draws the options.
+    #[cfg(not(feature = "gfx_glyph_text"))]
+    pub fn draw(&mut self, c: Context, g: &mut G2d, glyph_cache: &mut Glyphs) {
+        for option in self.options.iter_mut() {
+            option.draw(c, g, glyph_cache);
+        }
+    }
+    /// Draws the options.
+    #[cfg(feature = "gfx_glyph_text")]
+    pub fn draw(&self, c: Context, g: &mut G2d) {
+        for option in self.options.iter() {
+            option.draw(c, g);
+        }
+    }
+    /// Queues the text of the options into the brush.
+    #[cfg(feature = "gfx_glyph_text")]
+    pub fn draw_text(&self, brush: &mut GlyphBrush<Resources, GfxFactory>) {
+        for option in self.options.iter() {
+            option.draw_text(brush);
+        }
+    }
+}
+#[test]
+fn test_choice_menu() {
+    let mut menu = ChoiceMenu::new(Rect {
+        pos: Pos::new(0., 0.),
+        size: vec2![800., 600.],
+    });
+    menu.set_options(vec!["Forest".to_string(), "Home".to_string(), "Beach".to_string()]);
+    assert_eq!(menu.selected, 0);
+    menu.select_previous();
+    assert_eq!(menu.selected, 2);
+    menu.select_next();
+    assert_eq!(menu.selected, 0);
+    menu.select_next();
+    assert_eq!(menu.selected, 1);
+    // The options are 48 pixels tall with a 12 pixel gap between them, starting at (160, 120).
+    assert_eq!(menu.option_at(Pos::new(200., 130.)), Some(0));
+    assert_eq!(menu.option_at(Pos::new(200., 190.)), Some(1));
+    assert_eq!(menu.option_at(Pos::new(200., 175.)), None);
+    assert_eq!(menu.option_at(Pos::new(100., 130.)), None);
+    menu.clear();
+    menu.select_next();
+    assert_eq!(menu.selected, 0);
+    assert_eq!(menu.option_at(Pos::new(200., 130.)), None);
+}
@@ -0,0 +1,173 @@
+//! Turns text with tags like ``{b}`` into [`Span`]s that have their own [`Style`].
+//!
+//! The tags that are supported are:
+//! * ``{b}`` Bold text.
+//! * ``{i}`` Italic text.
+//! * ``{color=#ff0000}`` Coloured text. The colour can also have an alpha value like ``#ff000080``.
+//! * ``{size=20}`` Text with a different font size.
+//!
+//! Each tag is closed using the same name with a slash like ``{/b}`` or ``{/color}``.
+//! A tag that isn't closed lasts until the end of the text.
+//! Use ``{{`` to write a ``{`` and anything that isn't a tag is shown as it is.
+
+/// The style of a piece of text.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Style {
+    /// Whether the text is bold.
+    pub bold: bool,
+    /// Whether the text is italic.
+    pub italic: bool,
+    /// The colour of the text. ``None`` means the colour of the textbox is used.
+    pub color: Option<[f32; 4]>,
+    /// The font size of the text. ``None`` means the font size of the textbox is used.
+    pub size: Option<u32>,
+}
+
+/// A piece of text that has the same style all the way through.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Span {
+    /// The text.
+    pub text: String,
+    /// The style of the text.
+    pub style: Style,
+}
+
+enum Tag {
+    Bold,
+    Italic,
+    Color([f32; 4]),
+    Size(u32),
+    Close(String),
+}
+
+/// Turns text with tags into [`Span`]s.
+pub fn parse(text: &str) -> Vec<Span> {
+    let mut spans: Vec<Span> = Vec::new();
+    // Every tag that is opened adds a new style which is removed when it's closed.
+    let mut styles: Vec<(String, Style)> = Vec::new();
+    let mut current = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        current.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if rest.starts_with("{{") {
+            current.push('{');
+            rest = &rest[2..];
+            continue
+        }
+        let tag = rest.find('}')
+            .and_then(|end| parse_tag(&rest[1..end]).map(|tag| (tag, end)));
+        match tag {
+            Some((tag, end)) => {
+                let style = styles.last().map(|s| s.1).unwrap_or_default();
+                push(&mut spans, &mut current, style);
+                match tag {
+                    Tag::Bold => styles.push(("b".to_string(), Style { bold: true, ..style })),
+                    Tag::Italic => styles.push(("i".to_string(), Style { italic: true, ..style })),
+                    Tag::Color(c) => styles.push(("color".to_string(), Style { color: Some(c), ..style })),
+                    Tag::Size(s) => styles.push(("size".to_string(), Style { size: Some(s), ..style })),
+                    Tag::Close(name) => {
+                        if let Some(i) = styles.iter().rposition(|s| s.0 == name) {
+                            styles.remove(i);
+                            // The styles opened after the closed one no longer have its style.
+                            let mut style = if i == 0 { Style::default() } else { styles[i - 1].1 };
+                            for s in styles[i..].iter_mut() {
+                                style = apply(&s.0, s.1, style);
+                                s.1 = style;
+                            }
+                        }
+                    },
+                }
+                rest = &rest[end + 1..];
+            },
+            None => {
+                current.push('{');
+                rest = &rest[1..];
+            },
+        }
+    }
+    current.push_str(rest);
+    let style = styles.last().map(|s| s.1).unwrap_or_default();
+    push(&mut spans, &mut current, style);
+    spans
+}
+
+/// Returns the text of the spans without any of the styles.
+pub fn plain_text(spans: &[Span]) -> String {
+    spans.iter()
+        .map(|s| s.text.as_str())
+        .collect()
+}
+
+fn push(spans: &mut Vec<Span>, text: &mut String, style: Style) {
+    if !text.is_empty() {
+        spans.push(Span {
+            text: text.clone(),
+            style,
+        });
+        text.clear();
+    }
+}
+
+/// Adds the part of ``own`` that is set by the tag ``name`` onto ``base``.
+fn apply(name: &str, own: Style, base: Style) -> Style {
+    match name {
+        "b" => Style { bold: true, ..base },
+        "i" => Style { italic: true, ..base },
+        "color" => Style { color: own.color, ..base },
+        "size" => Style { size: own.size, ..base },
+        _ => base,
+    }
+}
+
+fn parse_tag(tag: &str) -> Option<Tag> {
+    let tag = tag.trim();
+    if tag.starts_with('/') {
+        let name = tag[1..].trim();
+        return match name {
+            "b" | "i" | "color" | "size" => Some(Tag::Close(name.to_string())),
+            _ => None,
+        }
+    }
+    let mut parts = tag.splitn(2, '=');
+    let name = parts.next()?.trim();
+    let value = parts.next().map(|v| v.trim());
+    match (name, value) {
+        ("b", None) => Some(Tag::Bold),
+        ("i", None) => Some(Tag::Italic),
+        ("color", Some(v)) => parse_color(v).map(Tag::Color),
+        ("size", Some(v)) => v.parse().ok().map(Tag::Size),
+        _ => None,
+    }
+}
+
+/// Parses a colour like ``#ff0000`` or ``#ff000080``.
+fn parse_color(text: &str) -> Option<[f32; 4]> {
+    if !text.starts_with('#') || !text.is_ascii() {
+        return None
+    }
+    let hex = &text[1..];
+    if hex.len() != 6 && hex.len() != 8 {
+        return None
+    }
+    let mut color = [1.; 4];
+    for (i, c) in color.iter_mut().enumerate().take(hex.len() / 2) {
+        *c = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()? as f32 / 255.;
+    }
+    Some(color)
+}
+
+#[test]
+fn test_markup() {
+    let spans = parse("Normal {b}bold {color=#ff0000}red{/b} still red{/color} {{b}");
+    assert_eq!(plain_text(&spans), "Normal bold red still red {b}");
+    assert_eq!(spans[2].style, Style { bold: true, color: Some([1., 0., 0., 1.]), ..Style::default() });
+    assert_eq!(spans[3].style, Style { color: Some([1., 0., 0., 1.]), ..Style::default() });
+    let spans = parse("{size=20}Big{/size} {unknown} {i}italic");
+    assert_eq!(plain_text(&spans), "Big {unknown} italic");
+    assert_eq!(spans, vec![
+        Span { text: "Big".to_string(), style: Style { size: Some(20), ..Style::default() } },
+        Span { text: " {unknown} ".to_string(), style: Style::default() },
+        Span { text: "italic".to_string(), style: Style { italic: true, ..Style::default() } },
+    ]);
+}
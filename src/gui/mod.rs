@@ -1,4 +1,4 @@
-//! Manages the UI, which consists of only the textbox for now.
+//! Manages the UI, which consists of the textboxes, the menu used for choices and the backlog.
 //!
 //! In the future it is planned to also be in control of on-screen buttons as well.
 
@@ -17,6 +17,9 @@ use ::{
 };
 
 pub mod textbox;
+pub mod choice;
+pub mod backlog;
+pub mod markup;
 mod grid;
 
 pub use self::{
@@ -25,6 +28,8 @@ pub use self::{
         TextBoxBuilder,
         Padding,
     },
+    choice::ChoiceMenu,
+    backlog::Backlog,
     grid::Grid,
 };
 use super::Rect;
@@ -36,6 +41,10 @@ pub struct Ui {
     /// The textbox that displays the name of the speaker.
     /// If ``None`` then nothing will be shown.
     pub speaker_box: Option<TextBox>,
+    /// The menu that shows the options when the player has to make a choice.
+    pub choices: ChoiceMenu,
+    /// The screen that lists the lines of dialogue that have already been shown.
+    pub backlog: Backlog,
 }
 
 impl Ui {
@@ -50,6 +59,32 @@ impl Ui {
         Ui {
             textbox: tb,
             speaker_box: None,
+            choices: ChoiceMenu::new(canvas),
+            backlog: Backlog::new(canvas),
+        }
+    }
+    /// Reveals more of the text of the textboxes.
+    pub fn update(&mut self, delta_time: f64) {
+        self.textbox.update(delta_time);
+        if let Some(ref mut e) = self.speaker_box {
+            e.update(delta_time);
+        }
+        for option in self.choices.options.iter_mut() {
+            option.update(delta_time);
+        }
+    }
+    /// Whether the text of the main textbox is still being revealed.
+    pub fn is_revealing(&self) -> bool {
+        self.textbox.is_revealing()
+    }
+    /// Reveals all of the text of the textboxes straight away.
+    pub fn finish_reveal(&mut self) {
+        self.textbox.finish_reveal();
+        if let Some(ref mut e) = self.speaker_box {
+            e.finish_reveal();
+        }
+        for option in self.choices.options.iter_mut() {
+            option.finish_reveal();
         }
     }
     /// Draws the components that the Ui contains.
@@ -59,6 +94,8 @@ impl Ui {
         if let Some(ref mut e) = self.speaker_box {
             e.draw(c, g, glyph_cache)
         }
+        self.choices.draw(c, g, glyph_cache);
+        self.backlog.draw(c, g, glyph_cache);
     }
     /// Draws the components that the Ui contains.
     #[cfg(feature = "gfx_glyph_text")]
@@ -67,6 +104,8 @@ impl Ui {
         if let Some(ref mut e) = self.speaker_box {
             e.draw(c, g)
         }
+        self.choices.draw(c, g);
+        self.backlog.draw(c, g);
     }
     /// Queues the text into the Brush that will be drawn with [`draw_2d_with_text`]
     #[cfg(feature = "gfx_glyph_text")]
@@ -75,5 +114,7 @@ impl Ui {
         if let Some(ref mut e) = self.speaker_box {
             e.draw_text(brush)
         }
+        self.choices.draw_text(brush);
+        self.backlog.draw_text(brush);
     }
 }
\ No newline at end of file
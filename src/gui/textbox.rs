@@ -1,6 +1,8 @@
 //! A module that lets you build and manage the textboxes shown in the game.
 //!
 //! use [`TextBoxBuilder'] to build a [`TextBox`]
+//!
+//! The text can be styled using tags like ``{b}``. See the [`markup`](../markup/index.html) module.
 
 use piston_window::{
     G2d,
@@ -16,17 +18,27 @@ use ::piston_window::{
     Transformed,
     character::CharacterCache,
 };
+#[cfg(not(feature = "gfx_glyph_text"))]
+use std::mem;
 #[cfg(feature = "gfx_glyph_text")]
 use ::{
     gfx_glyph::{
-        Section,
+        VariedSection,
+        SectionText,
         GlyphBrush,
         Scale,
+        FontId,
     },
     gfx_device_gl::Resources,
     piston_window::GfxFactory,
 };
-use super::Rect;
+use super::{
+    Rect,
+    markup::{
+        self,
+        Span,
+    },
+};
 use game::Game;
 
 /// A struct that represents and draws a textbox
@@ -38,12 +50,17 @@ pub struct TextBox {
     pub inner: Rect,
     /// The outer rectangle which is what the square is drawn using.
     pub outer: Rect,
-    /// The y position of the text.
+    /// The y position of the top of the text.
     pub text_pos: f64,
     /// The padding applied on the textbox.
     pub padding: Padding,
     /// Whether the text has been changed or not.
     pub text_changed: bool,
+    /// How many characters are revealed every second when the text is set.
+    /// ``None`` shows all of the text straight away.
+    pub chars_per_second: Option<f64>,
+    /// How many characters of the text have been revealed so far.
+    pub revealed: f64,
     // Only if we don't use the gfx_glyph crate to render text
     /// The Piston text primitive that is used.
     #[cfg(not(feature = "gfx_glyph_text"))]
@@ -51,16 +68,26 @@ pub struct TextBox {
     /// The font size that is used with the Piston text primitive.
     #[cfg(not(feature = "gfx_glyph_text"))]
     pub font_size: u32,
-    /// The text that is to be displayed. One item of the Vec is a line.
+    /// The text that is to be displayed. One item of the Vec is a line
+    /// which is made up of [`Span`]s of styled text.
     #[cfg(not(feature = "gfx_glyph_text"))]
-    pub text_v: Vec<String>,
+    pub text_v: Vec<Vec<Span>>,
     /// The gap between the lines.
     #[cfg(not(feature = "gfx_glyph_text"))]
     pub line_gap: u32,
     // If we are using the gfx_glyph crate to render text
-    /// The string that is to be drawn. gfx_glyph takes care of the wrapping.
+    /// The string that is to be drawn without any of the tags. gfx_glyph takes care of the wrapping.
     #[cfg(feature = "gfx_glyph_text")]
     pub text: String,
+    /// The text that is to be drawn split into [`Span`]s of styled text.
+    #[cfg(feature = "gfx_glyph_text")]
+    pub spans: Vec<Span>,
+    /// The font in the brush that is used for bold text.
+    #[cfg(feature = "gfx_glyph_text")]
+    pub bold_font: FontId,
+    /// The font in the brush that is used for italic text.
+    #[cfg(feature = "gfx_glyph_text")]
+    pub italic_font: FontId,
     /// The colour of the text.
     #[cfg(feature = "gfx_glyph_text")]
     pub color: [f32; 4],
@@ -80,6 +107,8 @@ impl TextBox {
             text_pos: 0.,
             padding,
             text_changed: false,
+            chars_per_second: None,
+            revealed: 0.,
             font_size: 13,
             text_v: vec![],
             line_gap: 7,
@@ -98,51 +127,139 @@ impl TextBox {
             text_pos: 0.,
             padding,
             text_changed: false,
+            chars_per_second: None,
+            revealed: 0.,
             font_scale: Scale::uniform(13.),
             text: String::new(),
+            spans: Vec::new(),
+            bold_font: FontId::default(),
+            italic_font: FontId::default(),
             color: [1.; 4]
         }
     }
     /// Sets the text that is shown by the textbox.
     #[cfg(not(feature = "gfx_glyph_text"))]
     pub fn set_text(&mut self, text: String) {
-        self.text_v = text.split('\n')
-            .fold(vec![], |mut lines, l| {
-                lines.push(l.to_string());
-                lines
-            });
+        let mut lines = vec![vec![]];
+        for span in markup::parse(&text) {
+            for (i, l) in span.text.split('\n').enumerate() {
+                if i > 0 {
+                    lines.push(vec![]);
+                }
+                if !l.is_empty() {
+                    if let Some(line) = lines.last_mut() {
+                        line.push(Span { text: l.to_string(), style: span.style });
+                    }
+                }
+            }
+        }
+        self.text_v = lines;
         self.text_changed = true;
+        self.revealed = 0.;
     }
     /// Sets the text that is shown by the textbox.
     #[cfg(feature = "gfx_glyph_text")]
     pub fn set_text(&mut self, text: String) {
-        self.text = text;
+        self.spans = markup::parse(&text);
+        self.text = markup::plain_text(&self.spans);
+        self.revealed = 0.;
+    }
+    /// The number of characters in the text without any of the tags.
+    #[cfg(not(feature = "gfx_glyph_text"))]
+    pub fn char_count(&self) -> usize {
+        self.text_v.iter()
+            .flat_map(|line| line.iter())
+            .map(|span| span.text.chars().count())
+            .sum()
+    }
+    /// The number of characters in the text without any of the tags.
+    #[cfg(feature = "gfx_glyph_text")]
+    pub fn char_count(&self) -> usize {
+        self.text.chars().count()
     }
-    fn calculate_inner(&mut self) {
+    /// Reveals more of the text if [`TextBox::chars_per_second`] is set.
+    pub fn update(&mut self, delta_time: f64) {
+        if let Some(speed) = self.chars_per_second {
+            let count = self.char_count() as f64;
+            self.revealed = (self.revealed + speed * delta_time).min(count);
+        }
+    }
+    /// Whether there is still text that hasn't been revealed.
+    pub fn is_revealing(&self) -> bool {
+        self.chars_per_second.is_some() && (self.revealed as usize) < self.char_count()
+    }
+    /// Reveals all of the text straight away.
+    pub fn finish_reveal(&mut self) {
+        self.revealed = self.char_count() as f64;
+    }
+    /// How many characters of the text are drawn.
+    fn shown_chars(&self) -> usize {
+        match self.chars_per_second {
+            Some(_) => self.revealed as usize,
+            None => usize::max_value(),
+        }
+    }
+    /// Recalculates the inner rectangle using the padding and the outer rectangle.
+    pub fn calculate_inner(&mut self) {
         self.inner = self.padding.calculate_inner_rect(self.outer);
     }
     #[cfg(not(feature = "gfx_glyph_text"))]
     fn position_text(&mut self) {
-        let y = self.inner.pos.y + (self.font_size as f64);
-        self.text_pos = y;
+        self.text_pos = self.inner.pos.y;
+    }
+    /// The width of a span of text in pixels.
+    #[cfg(not(feature = "gfx_glyph_text"))]
+    fn span_width(&self, cache: &mut Glyphs, span: &Span) -> f64 {
+        let size = span.style.size.unwrap_or(self.font_size);
+        let width = match cache.width(size, span.text.as_str()) {
+            Ok(e) => e,
+            Err(_) => 100.,
+        };
+        // Bold text is drawn twice with the second one moved a pixel to the right.
+        if span.style.bold { width + 1. } else { width }
+    }
+    #[cfg(not(feature = "gfx_glyph_text"))]
+    fn line_width(&self, cache: &mut Glyphs, line: &[Span]) -> f64 {
+        line.iter()
+            .map(|span| self.span_width(cache, span))
+            .sum()
     }
     // Pretty much copied from ggez
     #[cfg(not(feature = "gfx_glyph_text"))]
     fn wrap_text(&mut self, cache: &mut Glyphs) {
         let mut new_text = Vec::new();
         for line in self.text_v.iter() {
-            let mut current_line = String::new();
-            for word in line.split_whitespace() {
+            // A word can be made up of spans with different styles like "{b}Bo{/b}ld".
+            let mut words: Vec<Vec<Span>> = Vec::new();
+            let mut word = Vec::new();
+            for span in line.iter() {
+                for (i, piece) in span.text.split(char::is_whitespace).enumerate() {
+                    if i > 0 && !word.is_empty() {
+                        words.push(mem::replace(&mut word, Vec::new()));
+                    }
+                    if !piece.is_empty() {
+                        word.push(Span { text: piece.to_string(), style: span.style });
+                    }
+                }
+            }
+            if !word.is_empty() {
+                words.push(word);
+            }
+            let mut current_line: Vec<Span> = Vec::new();
+            for word in words {
                 let mut possible_line = current_line.clone();
-                if !possible_line.is_empty() { possible_line.push(' ') };
-                possible_line.push_str(word);
-                let text_width = match cache.width(self.font_size, possible_line.as_str()) {
-                    Ok(e) => e,
-                    Err(_) => 100.,
-                };
-                if text_width > self.inner.size.x {
+                if let Some(last) = possible_line.last_mut() {
+                    last.text.push(' ');
+                }
+                for span in word.iter() {
+                    push_span(&mut possible_line, span.clone());
+                }
+                if self.line_width(cache, &possible_line) > self.inner.size.x && !current_line.is_empty() {
                     new_text.push(current_line);
-                    current_line = word.to_string();
+                    current_line = Vec::new();
+                    for span in word {
+                        push_span(&mut current_line, span);
+                    }
                 } else {
                     current_line = possible_line;
                 }
@@ -168,15 +285,48 @@ impl TextBox {
             self.position_text();
             self.text_changed = false;
         }
-        for (i, l) in self.text_v.iter().enumerate() {
-            self.text_primitive
-                .draw(
-                    l.as_str(),
-                    glyph_cache,
-                    &DrawState::default(),
-                    c.transform.trans(self.inner.pos.x, self.text_pos + ((self.font_size + self.line_gap) * i as u32) as f64),
-                    g,
-                ).expect("Panicked when drawing text!");
+        let mut y = self.text_pos;
+        let mut shown = self.shown_chars();
+        for line in self.text_v.iter() {
+            let height = line.iter()
+                .map(|span| span.style.size.unwrap_or(self.font_size))
+                .max()
+                .unwrap_or(self.font_size);
+            y += height as f64;
+            let mut x = self.inner.pos.x;
+            for span in line.iter() {
+                let part = reveal(&span.text, &mut shown);
+                if part.is_empty() {
+                    return
+                }
+                let text = Text {
+                    color: span.style.color.unwrap_or(self.text_primitive.color),
+                    font_size: span.style.size.unwrap_or(self.font_size),
+                    ..self.text_primitive
+                };
+                let mut transform = c.transform.trans(x, y);
+                if span.style.italic {
+                    transform = transform.shear(-0.2, 0.);
+                }
+                text.draw(
+                        part,
+                        glyph_cache,
+                        &DrawState::default(),
+                        transform,
+                        g,
+                    ).expect("Panicked when drawing text!");
+                if span.style.bold {
+                    text.draw(
+                            part,
+                            glyph_cache,
+                            &DrawState::default(),
+                            transform.trans(1., 0.),
+                            g,
+                        ).expect("Panicked when drawing text!");
+                }
+                x += self.span_width(glyph_cache, span);
+            }
+            y += self.line_gap as f64;
         };
     }
     /// Draw the textbox.
@@ -193,18 +343,56 @@ impl TextBox {
     /// Queue the text of the textbox into the brush.
     #[cfg(feature = "gfx_glyph_text")]
     pub fn draw_text(&self, brush: &mut GlyphBrush<Resources, GfxFactory>) {
-        let section = Section {
-            text: &self.text,
+        let mut shown = self.shown_chars();
+        let text = self.spans.iter()
+            .map(|span| SectionText {
+                text: reveal(&span.text, &mut shown),
+                scale: span.style.size
+                    .map(|size| Scale::uniform(size as f32))
+                    .unwrap_or(self.font_scale),
+                color: span.style.color.unwrap_or(self.color),
+                font_id: if span.style.bold {
+                    self.bold_font
+                } else if span.style.italic {
+                    self.italic_font
+                } else {
+                    FontId::default()
+                },
+            })
+            .collect();
+        let section = VariedSection {
+            text,
             bounds: (self.inner.size.x as f32, self.inner.size.y as f32),
-            color: self.color,
             screen_position: (self.inner.pos.x as f32, self.inner.pos.y as f32),
-            scale: self.font_scale,
-            ..Section::default()
+            ..VariedSection::default()
         };
         brush.queue(section);
     }
 }
 
+/// Returns the start of the text that has at most ``count`` characters,
+/// taking the characters that are returned away from ``count``.
+fn reveal<'a>(text: &'a str, count: &mut usize) -> &'a str {
+    let end = text.char_indices()
+        .nth(*count)
+        .map(|(i, _)| i)
+        .unwrap_or_else(|| text.len());
+    *count -= text[..end].chars().count();
+    &text[..end]
+}
+
+/// Adds a span onto the end of a line, joining it with the last span if they have the same style.
+#[cfg(not(feature = "gfx_glyph_text"))]
+fn push_span(line: &mut Vec<Span>, span: Span) {
+    if let Some(last) = line.last_mut() {
+        if last.style == span.style {
+            last.text.push_str(&span.text);
+            return
+        }
+    }
+    line.push(span);
+}
+
 /// A builder struct that helps build a [`TextBox`].
 #[derive(Clone, Debug, Deserialize)]
 pub struct TextBoxBuilder {
@@ -213,8 +401,13 @@ pub struct TextBoxBuilder {
     font_size: Option<u32>,
     rectangle: Option<Rect>,
     padding: Option<Padding>,
+    chars_per_second: Option<f64>,
     #[cfg(not(feature = "gfx_glyph_text"))]
-    line_gap: Option<u32>
+    line_gap: Option<u32>,
+    #[cfg(feature = "gfx_glyph_text")]
+    bold_font: Option<usize>,
+    #[cfg(feature = "gfx_glyph_text")]
+    italic_font: Option<usize>,
 }
 impl TextBoxBuilder {
     /// Creates a new [`TextBoxBuilder`].
@@ -225,8 +418,13 @@ impl TextBoxBuilder {
             font_size: None,
             rectangle: None,
             padding: None,
+            chars_per_second: None,
             #[cfg(not(feature = "gfx_glyph_text"))]
             line_gap: None,
+            #[cfg(feature = "gfx_glyph_text")]
+            bold_font: None,
+            #[cfg(feature = "gfx_glyph_text")]
+            italic_font: None,
         }
     }
     /// Builds the [`TextBox`].
@@ -252,6 +450,7 @@ impl TextBoxBuilder {
         if let Some(size) = self.font_size {
             text_box.font_size = size;
         }
+        text_box.chars_per_second = self.chars_per_second;
         text_box
     }
     /// Builds the [`TextBox`].
@@ -272,6 +471,13 @@ impl TextBoxBuilder {
         if let Some(col) = self.text_colour {
             text_box.color = col;
         }
+        if let Some(font) = self.bold_font {
+            text_box.bold_font = FontId(font);
+        }
+        if let Some(font) = self.italic_font {
+            text_box.italic_font = FontId(font);
+        }
+        text_box.chars_per_second = self.chars_per_second;
         text_box
     }
     /// The colourr of the rectangle.
@@ -299,12 +505,30 @@ impl TextBoxBuilder {
         self.padding = Some(pad);
         self
     }
+    /// How many characters of the text are revealed every second.
+    /// The whole text is shown straight away if this isn't set.
+    pub fn with_chars_per_second(mut self, speed: f64) -> Self {
+        self.chars_per_second = Some(speed);
+        self
+    }
     /// The gap between the lines of the text.
     #[cfg(not(feature = "gfx_glyph_text"))]
     pub fn with_line_gap(mut self, gap: u32) -> Self {
         self.line_gap = Some(gap);
         self
     }
+    /// The index of the font in the brush that is used for bold text.
+    #[cfg(feature = "gfx_glyph_text")]
+    pub fn with_bold_font(mut self, font: usize) -> Self {
+        self.bold_font = Some(font);
+        self
+    }
+    /// The index of the font in the brush that is used for italic text.
+    #[cfg(feature = "gfx_glyph_text")]
+    pub fn with_italic_font(mut self, font: usize) -> Self {
+        self.italic_font = Some(font);
+        self
+    }
 
 }
 
@@ -359,4 +583,14 @@ impl Padding {
             None => outer_rect,
         }
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_reveal() {
+    let mut count = 3;
+    assert_eq!(reveal("Héllo", &mut count), "Hél");
+    assert_eq!(count, 0);
+    let mut count = 10;
+    assert_eq!(reveal("Hi", &mut count), "Hi");
+    assert_eq!(count, 8);
+}
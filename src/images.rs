@@ -18,6 +18,9 @@ use super::Rect;
 pub struct BackgroundImage {
     image: Image,
     texture: Option<Arc<G2dTexture>>,
+    /// The name of the background that is being shown.
+    /// This is only set when the background is set using its name.
+    pub name: Option<String>,
 }
 
 impl BackgroundImage {
@@ -29,6 +32,7 @@ impl BackgroundImage {
         BackgroundImage {
             image: Image::new().rect(canvas.to_slice()),
             texture: None,
+            name: None,
         }
     }
     /// Sets the texture of the background.
@@ -41,6 +45,7 @@ impl BackgroundImage {
     /// Clears the background.
     pub fn clear_texture(&mut self) {
         self.texture = None;
+        self.name = None;
     }
     /// Draws the background onto the screen.
     pub fn draw(&mut self, c: Context, g: &mut G2d) {
@@ -3,4 +3,18 @@
 pub enum GameEvent {
     /// Continue to the next step(s) of the story.
     Continue,
+    /// Select the option above the current one when making a choice.
+    Up,
+    /// Select the option below the current one when making a choice.
+    Down,
+    /// Go back to the previous line of dialogue.
+    Back,
+    /// Open or close the backlog of the lines of dialogue that have already been shown.
+    Backlog,
+    /// Turn auto mode on or off.
+    Auto,
+    /// Turn skip mode on or off.
+    Skip,
+    /// Play the voice line of the dialogue on screen again.
+    ReplayVoice,
 }
\ No newline at end of file
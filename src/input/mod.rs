@@ -1,7 +1,7 @@
 //! The Piston events are taken and turned into [`GameEvent`]s
 //!
 //! The events are then used to dictate what happens in the game.
-//! Currently on Continue is supported.
+//! Currently Continue, Up, Down, Back, Backlog, Auto, Skip and ReplayVoice are supported.
 //!
 //! This is done in this way so that multiple keys or buttons can be used to trigger
 //! the same GameEvent.
@@ -16,18 +16,46 @@ pub use self::event::GameEvent;
 #[derive(Debug)]
 pub struct GameInput {
     /// the buttons that continue the story of the game.
-    pub continue_: HashSet<Button>
+    pub continue_: HashSet<Button>,
+    /// The buttons that select the option above the current one in a choice.
+    pub up: HashSet<Button>,
+    /// The buttons that select the option below the current one in a choice.
+    pub down: HashSet<Button>,
+    /// The buttons that go back to the previous line of dialogue.
+    pub back: HashSet<Button>,
+    /// The buttons that open and close the backlog.
+    pub backlog: HashSet<Button>,
+    /// The buttons that turn auto mode on and off.
+    pub auto: HashSet<Button>,
+    /// The buttons that turn skip mode on and off.
+    pub skip: HashSet<Button>,
+    /// The buttons that play the voice line of the dialogue on screen again.
+    pub replay_voice: HashSet<Button>,
 }
 impl GameInput {
     /// Create a new [`GameInput`] struct
     pub fn new() -> Self {
         GameInput {
             continue_: HashSet::new(),
+            up: HashSet::new(),
+            down: HashSet::new(),
+            back: HashSet::new(),
+            backlog: HashSet::new(),
+            auto: HashSet::new(),
+            skip: HashSet::new(),
+            replay_voice: HashSet::new(),
         }
     }
     /// Handle a Piston Event and return a [`GameEvent`].
     pub fn handle_event(&self, button: &Button) -> Option<GameEvent> {
         if self.continue_.contains(button) { return Some(GameEvent::Continue) };
+        if self.up.contains(button) { return Some(GameEvent::Up) };
+        if self.down.contains(button) { return Some(GameEvent::Down) };
+        if self.back.contains(button) { return Some(GameEvent::Back) };
+        if self.backlog.contains(button) { return Some(GameEvent::Backlog) };
+        if self.auto.contains(button) { return Some(GameEvent::Auto) };
+        if self.skip.contains(button) { return Some(GameEvent::Skip) };
+        if self.replay_voice.contains(button) { return Some(GameEvent::ReplayVoice) };
         None
     }
     /// Add an event that will continue the story.
@@ -38,9 +66,44 @@ impl GameInput {
     pub fn add_continue_events(&mut self, buttons: HashSet<Button>) {
         self.continue_.extend(buttons);
     }
+    /// Add events that will select the option above the current one in a choice.
+    pub fn add_up_events(&mut self, buttons: HashSet<Button>) {
+        self.up.extend(buttons);
+    }
+    /// Add events that will select the option below the current one in a choice.
+    pub fn add_down_events(&mut self, buttons: HashSet<Button>) {
+        self.down.extend(buttons);
+    }
+    /// Add events that will go back to the previous line of dialogue.
+    pub fn add_back_events(&mut self, buttons: HashSet<Button>) {
+        self.back.extend(buttons);
+    }
+    /// Add events that will open and close the backlog.
+    pub fn add_backlog_events(&mut self, buttons: HashSet<Button>) {
+        self.backlog.extend(buttons);
+    }
+    /// Add events that will turn auto mode on and off.
+    pub fn add_auto_events(&mut self, buttons: HashSet<Button>) {
+        self.auto.extend(buttons);
+    }
+    /// Add events that will turn skip mode on and off.
+    pub fn add_skip_events(&mut self, buttons: HashSet<Button>) {
+        self.skip.extend(buttons);
+    }
+    /// Add events that will play the voice line of the dialogue on screen again.
+    pub fn add_replay_voice_events(&mut self, buttons: HashSet<Button>) {
+        self.replay_voice.extend(buttons);
+    }
     /// Add input in the form of a [`GameInput`] struct.
     /// Just joins all of the data together.
     pub fn add_input(&mut self, input: GameInput) {
         self.add_continue_events(input.continue_);
+        self.add_up_events(input.up);
+        self.add_down_events(input.down);
+        self.add_back_events(input.back);
+        self.add_backlog_events(input.backlog);
+        self.add_auto_events(input.auto);
+        self.add_skip_events(input.skip);
+        self.add_replay_voice_events(input.replay_voice);
     }
 }
\ No newline at end of file
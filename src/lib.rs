@@ -21,6 +21,7 @@ extern crate toml;
 extern crate serde;
 extern crate rodio;
 extern crate indexmap;
+extern crate bincode;
 extern crate piston_window;
 #[macro_use] extern crate nom;
 #[macro_use] extern crate coord;
@@ -38,6 +39,13 @@ pub mod input;
 pub mod error;
 pub mod music;
 pub mod animation;
+pub mod save;
+pub mod rollback;
+pub mod mode;
+pub mod read;
+pub mod check;
+pub mod reload;
+pub mod settings;
 
 pub use game::Game;
 pub use util::GameBuilder;
@@ -0,0 +1,191 @@
+//! Auto mode and skip mode which let the story continue without the player pressing anything.
+//!
+//! * Auto mode continues once the text has been revealed and the player has had time to read it.
+//! * Skip mode quickly goes through the dialogue, finishing any transitions on the way.
+//!
+//! Both of them stop when a choice has to be made or the story has ended.
+//! Skip mode also stops at a line that hasn't been read before unless [`PlayMode::skip_unread`] is set.
+
+use super::game::Game;
+
+/// Whether auto mode or skip mode is on and how fast they go.
+#[derive(Debug, Clone)]
+pub struct PlayMode {
+    /// Whether auto mode is on.
+    pub auto: bool,
+    /// Whether skip mode is on.
+    pub skip: bool,
+    /// How many seconds auto mode waits after the text has been revealed before continuing.
+    pub auto_delay: f64,
+    /// How many more seconds auto mode waits for every character of the text.
+    pub auto_delay_per_char: f64,
+    /// How many seconds skip mode shows each line for.
+    pub skip_delay: f64,
+    /// Whether skip mode also skips lines that haven't been read before.
+    pub skip_unread: bool,
+    /// How many seconds the current line has been waited on for.
+    timer: f64,
+    /// The line that was being waited on.
+    /// (ScriptIndex, AnchorIndex, Step)
+    line: (usize, usize, usize),
+}
+impl PlayMode {
+    /// Create a new [`PlayMode`] with both of the modes turned off.
+    pub fn new() -> PlayMode {
+        PlayMode {
+            auto: false,
+            skip: false,
+            auto_delay: 1.,
+            auto_delay_per_char: 0.05,
+            skip_delay: 0.05,
+            skip_unread: false,
+            timer: 0.,
+            line: (0, 0, 0),
+        }
+    }
+    /// How many seconds auto mode waits on a line with ``chars`` characters.
+    pub fn auto_time(&self, chars: usize) -> f64 {
+        self.auto_delay + self.auto_delay_per_char * chars as f64
+    }
+}
+
+impl Game {
+    /// Turns auto mode on or off. Turning it on turns skip mode off.
+    pub fn toggle_auto(&mut self) {
+        self.mode.auto = !self.mode.auto;
+        if self.mode.auto {
+            self.mode.skip = false;
+        }
+    }
+    /// Turns skip mode on or off. Turning it on turns auto mode off.
+    pub fn toggle_skip(&mut self) {
+        self.mode.skip = !self.mode.skip;
+        if self.mode.skip {
+            self.mode.auto = false;
+        }
+    }
+    /// Turns both auto mode and skip mode off.
+    pub fn stop_modes(&mut self) {
+        self.mode.auto = false;
+        self.mode.skip = false;
+    }
+    /// Continues the story if auto mode or skip mode is on and it is time to.
+    /// This is called by [`Game::handle_event`] on every update.
+    pub fn update_modes(&mut self, delta_time: f64) {
+        if !self.mode.auto && !self.mode.skip {
+            return
+        }
+        let unread = self.mode.skip && !self.mode.skip_unread && self.is_current_line_new();
+        if self.story.is_choosing() || self.story.is_ended() || unread {
+            self.stop_modes();
+            return
+        }
+        if self.ui.backlog.open {
+            return
+        }
+        // The timer starts again for every new line.
+        let line = (self.story.index.0, self.story.index.1, self.story.current_step());
+        if line != self.mode.line {
+            self.mode.line = line;
+            self.mode.timer = 0.;
+        }
+        self.mode.timer += delta_time;
+        if self.mode.skip {
+            self.ui.finish_reveal();
+            for i in self.stage.values_mut() {
+                i.finish();
+            }
+            if self.mode.timer >= self.mode.skip_delay {
+                self.mode.timer = 0.;
+                self.next_step();
+            }
+        } else if self.ui.is_revealing() || self.story.timer.is_some() {
+            // Wait until the text has been revealed. A WAIT continues on its own.
+            self.mode.timer = 0.;
+        } else if self.mode.timer >= self.mode.auto_time(self.ui.textbox.char_count()) {
+            self.mode.timer = 0.;
+            self.next_step();
+        }
+    }
+}
+
+#[test]
+fn test_auto_time() {
+    let mode = PlayMode::new();
+    assert!(mode.auto_time(100) > mode.auto_time(10));
+    assert_eq!(mode.auto_time(0), mode.auto_delay);
+}
+
+#[test]
+fn test_auto_mode() {
+    let mut game = Game::new([800., 600.]);
+    game.story.load_from_str("main".to_string(), r#"
+    :start
+        "One"
+        "Two"
+        WAIT 1.0
+        "Three"
+        CHOICE
+            "Go on" -> :next
+        ENDCHOICE
+    :next
+        "Four"
+        END
+    "#).unwrap();
+    game.story.set_script("main", None);
+    game.next_step();
+    let text = |game: &Game| game.ui.backlog.lines.back().map(|l| l.1.clone()).unwrap_or_default();
+    game.toggle_auto();
+    game.update_modes(1.);
+    assert_eq!(text(&game), "One");
+    game.update_modes(game.mode.auto_time(3) - 1.);
+    assert_eq!(text(&game), "Two");
+    game.update_modes(5.);
+    // Auto mode leaves the WAIT to continue on its own.
+    assert!(game.story.timer.is_some());
+    game.update_modes(5.);
+    assert!(game.mode.auto);
+    assert!(game.story.timer.is_some());
+    game.next_step();
+    assert_eq!(text(&game), "Three");
+    game.update_modes(5.);
+    assert!(game.story.is_choosing());
+    game.update_modes(5.);
+    assert!(!game.mode.auto);
+    game.select_choice(0);
+    game.toggle_auto();
+    game.update_modes(5.);
+    assert!(game.story.is_ended());
+    game.update_modes(5.);
+    assert!(!game.mode.auto);
+}
+
+#[test]
+fn test_skip_mode() {
+    let mut game = Game::new([800., 600.]);
+    game.story.load_from_str("main".to_string(), r#"
+    :start
+        "One"
+        "Two"
+        "Three"
+    "#).unwrap();
+    game.read.insert("main", "start", 0);
+    game.read.insert("main", "start", 1);
+    game.ui.textbox.chars_per_second = Some(10.);
+    game.story.set_script("main", None);
+    game.next_step();
+    assert!(game.ui.is_revealing());
+    game.toggle_skip();
+    game.update_modes(0.01);
+    // Skip mode finishes what is happening on screen before going to the next line.
+    assert!(!game.ui.is_revealing());
+    assert_eq!(game.story.current_step(), 0);
+    game.update_modes(1.);
+    assert_eq!(game.story.current_step(), 1);
+    game.update_modes(1.);
+    // The third line hasn't been read and unread lines aren't skipped.
+    assert_eq!(game.story.current_step(), 2);
+    game.update_modes(1.);
+    assert!(!game.mode.skip);
+    assert_eq!(game.story.current_step(), 2);
+}
@@ -1,13 +1,20 @@
-//! The music that is stored and played by the game.
+//! The music, sound effects and voice lines that are stored and played by the game.
+//!
+//! Only the paths of the music are kept and each track is read from its file while it is playing.
+//! Sound effects and voice lines are short so they are kept in memory.
+
+pub mod stream;
+pub mod sfx;
+pub mod voice;
+pub mod volume;
 
 use std::{
+    mem,
     fs::File,
-    io::{
-        Read,
-        Cursor,
+    path::{
+        Path,
+        PathBuf,
     },
-    path::Path,
-    sync::Arc,
     collections::HashMap,
 };
 use ::{
@@ -20,21 +27,43 @@ use ::{
         load_music_from_str,
     },
 };
+use self::{
+    stream::Stream,
+    sfx::Sfx,
+    voice::Voice,
+    volume::{
+        Channel,
+        Volume,
+    },
+};
 use rodio::{
     Sink,
-    Source,
-    Decoder,
+    Device,
     default_output_device,
 };
 
 /// The struct in charge of handling the music for the game.
 pub struct Music {
-    /// The Rodio Sink that music can be played in.
+    /// The Rodio Sink that the current music is played in.
     pub sink: Sink,
-    /// A collection of music data in bytes.
-    pub library: HashMap<String, Arc<[u8]>>,
+    /// The paths of the music files.
+    pub library: HashMap<String, PathBuf>,
     /// Whether the music set will loop or not.
     pub loop_: bool,
+    /// The name of the music that was last set.
+    pub current: Option<String>,
+    /// The sound effects which are played over the music.
+    pub sfx: Sfx,
+    /// The voice lines of the dialogue.
+    pub voice: Voice,
+    /// The volume of each channel.
+    volume: Volume,
+    /// The device that the music is played on.
+    device: Device,
+    /// How the current music is fading in.
+    fade_in: Option<Fade>,
+    /// The music that was replaced or stopped and is fading out.
+    fading_out: Vec<(Sink, Fade)>,
 }
 impl Music {
     /// Create a new [`Music`] struct. Returns an error if not possible.
@@ -44,51 +73,190 @@ impl Music {
                 sink: Sink::new(&d),
                 library: HashMap::new(),
                 loop_: true,
+                current: None,
+                sfx: Sfx::new(d.clone()),
+                voice: Voice::new(d.clone()),
+                volume: Volume::new(),
+                device: d,
+                fade_in: None,
+                fading_out: Vec::new(),
             })
         } else {
             Err(MusicError::NoDefaultOutputDeviceFound)
         }
     }
-    /// Sets the music that is to be played.
+    /// Sets the music that is to be played, replacing the music that is playing.
     pub fn set_music(&mut self, music: &str) -> Result<(), MusicError> {
-        if let Some(m) = self.library.get(music) {
-            let c = Cursor::new(m.clone());
-            let d = Decoder::new(c)?;
-            if self.loop_ {
-                self.sink.append(d.repeat_infinite());
-            } else {
-                self.sink.append(d);
-            }
+        self.fade_to(music, 0.)
+    }
+    /// Crossfades from the music that is playing to other music over ``fade`` seconds.
+    /// The file is opened and decoded while it is playing and closed once it stops.
+    /// Nothing happens if the music doesn't exist.
+    pub fn fade_to(&mut self, music: &str, fade: f64) -> Result<(), MusicError> {
+        if let Some(path) = self.library.get(music) {
+            let mut sink = Sink::new(&self.device);
+            sink.append(Stream::open(path.clone(), self.loop_)?);
+            sink.set_volume(if fade > 0. { 0. } else { self.music_volume() });
+            let old = mem::replace(&mut self.sink, sink);
+            self.fade_out(old, fade);
+            self.fade_in = Fade::new(fade);
             self.sink.play();
+            self.current = Some(music.to_string());
         }
         Ok(())
     }
+    /// Stops the music, fading it out over ``fade`` seconds.
+    pub fn stop(&mut self, fade: f64) {
+        let old = mem::replace(&mut self.sink, Sink::new(&self.device));
+        self.fade_out(old, fade);
+        self.fade_in = None;
+        self.current = None;
+    }
+    /// Pauses the music, including any music that is fading.
+    pub fn pause(&mut self) {
+        self.sink.pause();
+        for &(ref s, _) in self.fading_out.iter() {
+            s.pause();
+        }
+    }
+    /// Continues playing the music after it was paused.
+    pub fn resume(&mut self) {
+        self.sink.play();
+        for &(ref s, _) in self.fading_out.iter() {
+            s.play();
+        }
+    }
+    /// Whether the music has been paused.
+    pub fn is_paused(&self) -> bool {
+        self.sink.is_paused()
+    }
+    /// Changes the volume of the music that is fading.
+    /// This should be called every frame with the time since the last frame.
+    pub fn update(&mut self, dt: f64) {
+        if self.is_paused() {
+            return
+        }
+        if let Some(mut fade) = self.fade_in.take() {
+            fade.elapsed += dt;
+            self.sink.set_volume(self.music_volume() * fade.progress() as f32);
+            if !fade.is_done() {
+                self.fade_in = Some(fade);
+            }
+        }
+        let volume = self.music_volume();
+        for &mut (ref mut sink, ref mut fade) in self.fading_out.iter_mut() {
+            fade.elapsed += dt;
+            sink.set_volume(volume * (1. - fade.progress() as f32));
+        }
+        // Dropping a sink stops its music.
+        self.fading_out.retain(|&(ref sink, ref fade)| !fade.is_done() && !sink.empty());
+    }
+    /// The volume of each channel.
+    pub fn volume(&self) -> Volume {
+        self.volume
+    }
+    /// Sets the volume of a channel from 0 to 1.
+    /// The music, sound effects and voice lines that are playing change straight away.
+    pub fn set_volume(&mut self, channel: Channel, volume: f32) {
+        let mut v = self.volume;
+        v.set(channel, volume);
+        self.set_volumes(v);
+    }
+    /// Sets the volume of every channel.
+    pub fn set_volumes(&mut self, volume: Volume) {
+        self.volume = volume;
+        // Music that is fading has its volume set by the next update.
+        if self.fade_in.is_none() {
+            let v = self.music_volume();
+            self.sink.set_volume(v);
+        }
+        self.sfx.set_volume(volume.effective(Channel::Sfx));
+        self.voice.set_volume(volume.effective(Channel::Voice));
+    }
+    fn music_volume(&self) -> f32 {
+        self.volume.effective(Channel::Music)
+    }
+    /// Fades out a sink that was playing music, or stops it straight away if ``fade`` is 0.
+    fn fade_out(&mut self, sink: Sink, fade: f64) {
+        match Fade::new(fade) {
+            Some(fade) if !sink.empty() => self.fading_out.push((sink, fade)),
+            _ => sink.stop(),
+        }
+    }
     /// Add music from a file.
-    pub fn add_music_from_file<P: AsRef<Path>, S: ToString>(&mut self, name: S, path: P)
+    /// The file isn't read until the music is played.
+    /// Returns an error if the file can't be opened.
+    pub fn add_music_from_file<P: Into<PathBuf>, S: ToString>(&mut self, name: S, path: P)
         -> Result<(), MusicError> {
-        let mut buf = Vec::new();
-        File::open(path)?.read_to_end(&mut buf)?;
-        self.add_music(name.to_string(), buf);
+        let path = path.into();
+        File::open(&path)?;
+        self.add_music(name.to_string(), path);
         Ok(())
     }
-    /// Add the buffered data of a music file.
-    pub fn add_music(&mut self, name: String, music: Vec<u8>) {
-        self.library.insert(name, Arc::from(music));
+    /// Add the path of a music file without checking that it exists.
+    pub fn add_music<P: Into<PathBuf>>(&mut self, name: String, path: P) {
+        self.library.insert(name, path.into());
     }
     /// If ``True`` will cause the music that is played to loop infinitely.
     pub fn set_loop(&mut self, loop_: bool) {
         self.loop_ = loop_;
     }
     /// Loads music from a TOML file.
+    /// Sound effects are loaded from the ``[sfx]`` section and voice lines from the ``[voice]`` section.
     pub fn load_from_config_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), ConfigImportError> {
-        let map = load_music_from_file(path)?;
-        self.library.extend(map);
+        let library = load_music_from_file(path)?;
+        self.library.extend(library.music);
+        self.sfx.library.extend(library.sfx);
+        self.voice.library.extend(library.voice);
         Ok(())
     }
     /// Loads music from a [`&str`] using TOML syntax.
+    /// Sound effects are loaded from the ``[sfx]`` section and voice lines from the ``[voice]`` section.
     pub fn load_from_config_str(&mut self, text: &str) -> Result<(), ConfigImportError> {
-        let map = load_music_from_str(text)?;
-        self.library.extend(map);
+        let library = load_music_from_str(text)?;
+        self.library.extend(library.music);
+        self.sfx.library.extend(library.sfx);
+        self.voice.library.extend(library.voice);
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// How far through fading some music is.
+struct Fade {
+    /// How long the fade has been going for in seconds.
+    elapsed: f64,
+    /// How long the fade lasts in seconds.
+    duration: f64,
+}
+impl Fade {
+    /// Returns ``None`` if the fade doesn't last any time.
+    fn new(duration: f64) -> Option<Fade> {
+        if duration > 0. {
+            Some(Fade {
+                elapsed: 0.,
+                duration,
+            })
+        } else {
+            None
+        }
+    }
+    /// How far through the fade is from 0 to 1.
+    fn progress(&self) -> f64 {
+        (self.elapsed / self.duration).min(1.)
+    }
+    fn is_done(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+#[test]
+fn test_fade() {
+    assert!(Fade::new(0.).is_none());
+    let mut fade = Fade::new(2.).unwrap();
+    fade.elapsed += 0.5;
+    assert_eq!(fade.progress(), 0.25);
+    assert!(!fade.is_done());
+    fade.elapsed += 2.;
+    assert_eq!(fade.progress(), 1.);
+    assert!(fade.is_done());
+}
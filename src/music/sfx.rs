@@ -0,0 +1,108 @@
+//! Sound effects which are played once and can overlap each other.
+//!
+//! Every sound effect is played in its own sink so that they don't wait for each other.
+//! Only [`Sfx::max_sounds`] can be playing at once and when another is played the oldest
+//! one is stopped.
+
+use std::{
+    fs::File,
+    io::{
+        Read,
+        Cursor,
+    },
+    path::Path,
+    sync::Arc,
+    collections::{
+        HashMap,
+        VecDeque,
+    },
+};
+use rodio::{
+    Sink,
+    Decoder,
+    Device,
+};
+use ::error::MusicError;
+
+/// How many sound effects can be playing at once by default.
+pub const DEFAULT_MAX_SOUNDS: usize = 8;
+
+/// The struct in charge of playing the sound effects of the game.
+pub struct Sfx {
+    /// The device that the sound effects are played on.
+    device: Device,
+    /// A collection of sound effect data in bytes.
+    pub library: HashMap<String, Arc<[u8]>>,
+    /// How many sound effects can be playing at once.
+    pub max_sounds: usize,
+    /// The volume that the sound effects are played at.
+    volume: f32,
+    /// The sound effects that are playing, from oldest to newest.
+    sinks: VecDeque<Sink>,
+}
+impl Sfx {
+    /// Create a new [`Sfx`] struct that plays sound effects on a device.
+    pub fn new(device: Device) -> Self {
+        Sfx {
+            device,
+            library: HashMap::new(),
+            max_sounds: DEFAULT_MAX_SOUNDS,
+            volume: 1.,
+            sinks: VecDeque::new(),
+        }
+    }
+    /// Plays a sound effect once.
+    /// If too many sound effects are playing then the oldest one is stopped.
+    /// Nothing happens if the sound effect doesn't exist.
+    pub fn play(&mut self, name: &str) -> Result<(), MusicError> {
+        let data = match self.library.get(name) {
+            Some(e) => e.clone(),
+            None => return Ok(()),
+        };
+        let decoder = Decoder::new(Cursor::new(data))?;
+        self.sinks.retain(|s| !s.empty());
+        while !self.sinks.is_empty() && self.sinks.len() >= self.max_sounds {
+            if let Some(s) = self.sinks.pop_front() {
+                s.stop();
+            }
+        }
+        if self.max_sounds > 0 {
+            let mut sink = Sink::new(&self.device);
+            sink.set_volume(self.volume);
+            sink.append(decoder);
+            sink.play();
+            self.sinks.push_back(sink);
+        }
+        Ok(())
+    }
+    /// How many sound effects are playing.
+    pub fn playing(&mut self) -> usize {
+        self.sinks.retain(|s| !s.empty());
+        self.sinks.len()
+    }
+    /// Sets the volume of the sound effects, including the ones that are playing.
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume;
+        for s in self.sinks.iter_mut() {
+            s.set_volume(volume);
+        }
+    }
+    /// Stops every sound effect that is playing.
+    pub fn stop(&mut self) {
+        for s in self.sinks.drain(..) {
+            s.stop();
+        }
+    }
+    /// Add a sound effect from a file.
+    pub fn add_sfx_from_file<P: AsRef<Path>, S: ToString>(&mut self, name: S, path: P)
+        -> Result<(), MusicError> {
+        let mut buf = Vec::new();
+        File::open(path)?.read_to_end(&mut buf)?;
+        self.add_sfx(name.to_string(), buf);
+        Ok(())
+    }
+    /// Add the buffered data of a sound effect file.
+    pub fn add_sfx(&mut self, name: String, sfx: Vec<u8>) {
+        self.library.insert(name, Arc::from(sfx));
+    }
+}
@@ -0,0 +1,105 @@
+//! Music that is decoded from its file while it is being played.
+//!
+//! Only a small part of the file is kept in memory at a time and the file is closed
+//! as soon as the music stops, so a game can have lots of long tracks.
+
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{
+        Path,
+        PathBuf,
+    },
+    time::Duration,
+};
+use rodio::{
+    Source,
+    Decoder,
+};
+use ::error::MusicError;
+
+/// A music file that is decoded as it is played.
+pub struct Stream {
+    /// The file that is being played.
+    path: PathBuf,
+    /// Whether the music starts again from the beginning when it ends.
+    loop_: bool,
+    decoder: Decoder<BufReader<File>>,
+}
+impl Stream {
+    /// Opens a music file so that it can be played.
+    pub fn open<P: Into<PathBuf>>(path: P, loop_: bool) -> Result<Stream, MusicError> {
+        let path = path.into();
+        let decoder = decode(&path)?;
+        Ok(Stream {
+            path,
+            loop_,
+            decoder,
+        })
+    }
+}
+
+fn decode(path: &Path) -> Result<Decoder<BufReader<File>>, MusicError> {
+    Ok(Decoder::new(BufReader::new(File::open(path)?))?)
+}
+
+impl Iterator for Stream {
+    type Item = i16;
+    fn next(&mut self) -> Option<i16> {
+        match self.decoder.next() {
+            Some(e) => Some(e),
+            // The file is opened again instead of keeping the whole track in memory to repeat it.
+            None if self.loop_ => match decode(&self.path) {
+                Ok(d) => {
+                    self.decoder = d;
+                    self.decoder.next()
+                },
+                Err(_) => None,
+            },
+            None => None,
+        }
+    }
+}
+
+impl Source for Stream {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.decoder.current_frame_len()
+    }
+    fn channels(&self) -> u16 {
+        self.decoder.channels()
+    }
+    fn sample_rate(&self) -> u32 {
+        self.decoder.sample_rate()
+    }
+    fn total_duration(&self) -> Option<Duration> {
+        if self.loop_ {
+            None
+        } else {
+            self.decoder.total_duration()
+        }
+    }
+}
+
+#[test]
+fn test_stream() {
+    use std::{
+        env,
+        fs,
+        io::Write,
+    };
+    // A tiny wav file with 4 samples.
+    let mut wav = Vec::new();
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&[44, 0, 0, 0]);
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&[16, 0, 0, 0, 1, 0, 1, 0, 0x44, 0xac, 0, 0, 0x88, 0x58, 1, 0, 2, 0, 16, 0]);
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&[8, 0, 0, 0, 1, 0, 2, 0, 3, 0, 4, 0]);
+    let dir = env::temp_dir().join("gobu_stream_test");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("track.wav");
+    File::create(&path).unwrap().write_all(&wav).unwrap();
+    assert_eq!(Stream::open(&path, false).unwrap().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+    assert_eq!(Stream::open(&path, true).unwrap().take(6).collect::<Vec<_>>(), vec![1, 2, 3, 4, 1, 2]);
+    assert!(Stream::open(dir.join("missing.wav"), false).is_err());
+}
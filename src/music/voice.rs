@@ -0,0 +1,111 @@
+//! Voice lines which are played while a line of dialogue is on screen.
+//!
+//! Only one voice line is played at a time and it is stopped when the story continues.
+
+use std::{
+    fs::File,
+    io::{
+        Read,
+        Cursor,
+    },
+    path::Path,
+    sync::Arc,
+    collections::HashMap,
+};
+use rodio::{
+    Sink,
+    Decoder,
+    Device,
+};
+use ::{
+    error::MusicError,
+    game::Game,
+};
+
+/// The struct in charge of playing the voice lines of the game.
+pub struct Voice {
+    /// The device that the voice lines are played on.
+    device: Device,
+    /// A collection of voice line data in bytes.
+    pub library: HashMap<String, Arc<[u8]>>,
+    /// The name of the voice line of the dialogue on screen.
+    pub current: Option<String>,
+    /// The volume that the voice lines are played at.
+    volume: f32,
+    /// The voice line that is playing.
+    sink: Option<Sink>,
+}
+impl Voice {
+    /// Create a new [`Voice`] struct that plays voice lines on a device.
+    pub fn new(device: Device) -> Self {
+        Voice {
+            device,
+            library: HashMap::new(),
+            current: None,
+            volume: 1.,
+            sink: None,
+        }
+    }
+    /// Stops the voice line that is playing and plays another one.
+    /// Nothing is played if the voice line doesn't exist.
+    pub fn play(&mut self, name: &str) -> Result<(), MusicError> {
+        self.stop();
+        self.current = Some(name.to_string());
+        self.replay()
+    }
+    /// Plays the voice line of the dialogue on screen again from the start.
+    pub fn replay(&mut self) -> Result<(), MusicError> {
+        if let Some(s) = self.sink.take() {
+            s.stop();
+        }
+        let data = match self.current.as_ref().and_then(|name| self.library.get(name)) {
+            Some(e) => e.clone(),
+            None => return Ok(()),
+        };
+        let mut sink = Sink::new(&self.device);
+        sink.set_volume(self.volume);
+        sink.append(Decoder::new(Cursor::new(data))?);
+        sink.play();
+        self.sink = Some(sink);
+        Ok(())
+    }
+    /// Sets the volume of the voice lines, including the one that is playing.
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume;
+        if let Some(ref mut s) = self.sink {
+            s.set_volume(volume);
+        }
+    }
+    /// Stops the voice line that is playing.
+    pub fn stop(&mut self) {
+        if let Some(s) = self.sink.take() {
+            s.stop();
+        }
+        self.current = None;
+    }
+    /// Whether a voice line is playing.
+    pub fn is_playing(&self) -> bool {
+        self.sink.as_ref().map_or(false, |s| !s.empty())
+    }
+    /// Add a voice line from a file.
+    pub fn add_voice_from_file<P: AsRef<Path>, S: ToString>(&mut self, name: S, path: P)
+        -> Result<(), MusicError> {
+        let mut buf = Vec::new();
+        File::open(path)?.read_to_end(&mut buf)?;
+        self.add_voice(name.to_string(), buf);
+        Ok(())
+    }
+    /// Add the buffered data of a voice line file.
+    pub fn add_voice(&mut self, name: String, voice: Vec<u8>) {
+        self.library.insert(name, Arc::from(voice));
+    }
+}
+
+impl Game {
+    /// Plays the voice line of the dialogue on screen again from the start.
+    pub fn replay_voice(&mut self) {
+        if let Some(ref mut m) = self.music {
+            let _ = m.voice.replay();
+        }
+    }
+}
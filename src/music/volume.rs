@@ -0,0 +1,97 @@
+//! The volume of each channel of audio in the game.
+//!
+//! Every channel is multiplied by the master volume so turning it down makes everything quieter.
+
+/// A channel of audio that has its own volume.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Channel {
+    /// Changes the volume of every other channel.
+    Master,
+    /// The music.
+    Music,
+    /// The sound effects.
+    Sfx,
+    /// The voice lines.
+    Voice,
+}
+impl Channel {
+    /// Gets a channel from its name in a script, like ``music``.
+    pub fn from_name(name: &str) -> Option<Channel> {
+        match name.to_lowercase().as_str() {
+            "master" => Some(Channel::Master),
+            "music" => Some(Channel::Music),
+            "sfx" => Some(Channel::Sfx),
+            "voice" => Some(Channel::Voice),
+            _ => None,
+        }
+    }
+}
+
+/// The volume of each channel from 0 (silent) to 1 (full volume).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Volume {
+    /// The volume that every channel is multiplied by.
+    pub master: f32,
+    /// The volume of the music.
+    pub music: f32,
+    /// The volume of the sound effects.
+    pub sfx: f32,
+    /// The volume of the voice lines.
+    pub voice: f32,
+}
+impl Default for Volume {
+    fn default() -> Volume {
+        Volume {
+            master: 1.,
+            music: 1.,
+            sfx: 1.,
+            voice: 1.,
+        }
+    }
+}
+impl Volume {
+    /// Create a new [`Volume`] with every channel at full volume.
+    pub fn new() -> Volume {
+        Volume::default()
+    }
+    /// The volume of a channel.
+    pub fn get(&self, channel: Channel) -> f32 {
+        match channel {
+            Channel::Master => self.master,
+            Channel::Music => self.music,
+            Channel::Sfx => self.sfx,
+            Channel::Voice => self.voice,
+        }
+    }
+    /// Sets the volume of a channel. The volume is kept between 0 and 1.
+    pub fn set(&mut self, channel: Channel, volume: f32) {
+        let volume = if volume.is_nan() { 0. } else { volume.max(0.).min(1.) };
+        match channel {
+            Channel::Master => self.master = volume,
+            Channel::Music => self.music = volume,
+            Channel::Sfx => self.sfx = volume,
+            Channel::Voice => self.voice = volume,
+        }
+    }
+    /// The volume that a channel is played at after the master volume is applied.
+    pub fn effective(&self, channel: Channel) -> f32 {
+        match channel {
+            Channel::Master => self.master,
+            _ => self.master * self.get(channel),
+        }
+    }
+}
+
+#[test]
+fn test_volume() {
+    let mut volume = Volume::new();
+    volume.set(Channel::Master, 0.5);
+    volume.set(Channel::Music, 0.5);
+    volume.set(Channel::Sfx, 2.);
+    assert_eq!(volume.effective(Channel::Music), 0.25);
+    assert_eq!(volume.get(Channel::Sfx), 1.);
+    assert_eq!(volume.effective(Channel::Voice), 0.5);
+    assert_eq!(Channel::from_name("Voice"), Some(Channel::Voice));
+    assert_eq!(Channel::from_name("drums"), None);
+}
@@ -0,0 +1,170 @@
+//! Keeps track of every line of dialogue the player has ever seen.
+//!
+//! The lines are kept in their own file which isn't part of any save slot so that
+//! a line that was read in one playthrough is still read in the next.
+//! Skip mode uses this to only skip text that the player has already read.
+
+use std::{
+    fs::{
+        self,
+        File,
+    },
+    io::{
+        Read,
+        Write,
+    },
+    path::PathBuf,
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+};
+use toml;
+
+use super::{
+    game::Game,
+    error::SaveError,
+};
+
+/// The name of the file in the save directory that the read lines are kept in.
+pub const READ_LINES_FILE: &str = "read_lines.toml";
+
+/// The lines of dialogue that have been read.
+#[derive(Debug, Clone, Default)]
+pub struct ReadLines {
+    /// The steps that have been read in each anchor of each script.
+    /// Script -> Anchor -> Steps
+    pub lines: BTreeMap<String, BTreeMap<String, BTreeSet<usize>>>,
+    /// The file that the lines are written to every time a new line is read.
+    /// ``None`` means that the lines aren't written anywhere.
+    pub path: Option<PathBuf>,
+    /// Whether the line currently on screen hadn't been read before it was shown.
+    current_is_new: bool,
+}
+impl ReadLines {
+    /// Create a new [`ReadLines`] without any read lines.
+    pub fn new() -> ReadLines {
+        ReadLines::default()
+    }
+    /// Whether a line has been read.
+    pub fn is_read(&self, script: &str, anchor: &str, step: usize) -> bool {
+        self.lines.get(script)
+            .and_then(|anchors| anchors.get(anchor))
+            .map(|steps| steps.contains(&step))
+            .unwrap_or(false)
+    }
+    /// Marks a line as read. Returns ``true`` if it hadn't been read before.
+    pub fn insert(&mut self, script: &str, anchor: &str, step: usize) -> bool {
+        self.lines.entry(script.to_string())
+            .or_insert_with(BTreeMap::new)
+            .entry(anchor.to_string())
+            .or_insert_with(BTreeSet::new)
+            .insert(step)
+    }
+    /// Reads the lines from a [`&str`] using TOML.
+    pub fn from_toml(text: &str) -> Result<BTreeMap<String, BTreeMap<String, BTreeSet<usize>>>, SaveError> {
+        Ok(toml::from_str(text)?)
+    }
+    /// Turns the lines into a [`String`] using TOML.
+    pub fn to_toml(&self) -> Result<String, SaveError> {
+        Ok(toml::to_string(&self.lines)?)
+    }
+}
+
+impl Game {
+    /// Marks the line that is on screen as read.
+    /// This is done by the story every time it stops on dialogue.
+    pub fn mark_read(&mut self) {
+        let step = self.story.current_step();
+        let line = self.story.names(self.story.index)
+            .map(|(script, anchor)| (script.to_string(), anchor.to_string()));
+        // Whether the line is new is decided before it is marked as read.
+        let is_new = match line {
+            Some((ref script, ref anchor)) => !self.read.is_read(script, anchor, step),
+            None => false,
+        };
+        self.read.current_is_new = is_new;
+        if let Some((ref script, ref anchor)) = line {
+            self.read.insert(script, anchor, step);
+        }
+        if is_new && self.read.path.is_some() {
+            // Losing a read line isn't worth stopping the game for.
+            let _ = self.save_read_lines();
+        }
+    }
+    /// Whether the line on screen hadn't been read before it was shown.
+    /// This can be used to mark new lines in the UI.
+    pub fn is_current_line_new(&self) -> bool {
+        self.read.current_is_new
+    }
+    /// Writes the read lines to [`ReadLines::path`] if it is set.
+    pub fn save_read_lines(&self) -> Result<(), SaveError> {
+        if let Some(ref path) = self.read.path {
+            let text = self.read.to_toml()?;
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            File::create(path)?.write_all(text.as_bytes())?;
+        }
+        Ok(())
+    }
+    /// Reads the lines from [`ReadLines::path`] if it is set and adds them to the read lines.
+    /// Nothing happens if the file doesn't exist yet.
+    pub fn load_read_lines(&mut self) -> Result<(), SaveError> {
+        let path = match self.read.path {
+            Some(ref path) if path.is_file() => path.clone(),
+            _ => return Ok(()),
+        };
+        let mut buffer = String::new();
+        File::open(path)?.read_to_string(&mut buffer)?;
+        for (script, anchors) in ReadLines::from_toml(&buffer)? {
+            for (anchor, steps) in anchors {
+                for step in steps {
+                    self.read.insert(&script, &anchor, step);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_read_lines() {
+    let mut read = ReadLines::new();
+    assert!(read.insert("main", "start", 2));
+    assert!(!read.insert("main", "start", 2));
+    read.insert("main", "park", 0);
+    assert!(read.is_read("main", "start", 2));
+    assert!(!read.is_read("main", "start", 3));
+    let text = read.to_toml().unwrap();
+    assert_eq!(ReadLines::from_toml(&text).unwrap(), read.lines);
+}
+
+#[test]
+fn test_skip_read_lines() {
+    let mut game = Game::new([800., 600.]);
+    game.story.load_from_str("main".to_string(), r#"
+    :start
+        "Aiko": "One"
+        "Two"
+        "Three"
+        "Four"
+    "#).unwrap();
+    game.read.insert("main", "start", 0);
+    game.read.insert("main", "start", 1);
+    game.story.set_script("main", None);
+    game.next_step();
+    assert!(!game.is_current_line_new());
+    game.toggle_skip();
+    game.update_modes(1.);
+    assert_eq!(game.story.current_step(), 1);
+    assert!(game.mode.skip);
+    game.update_modes(1.);
+    // The third line hasn't been read so skip mode stops on it.
+    assert_eq!(game.story.current_step(), 2);
+    assert!(game.is_current_line_new());
+    game.update_modes(1.);
+    assert!(!game.mode.skip);
+    assert_eq!(game.story.current_step(), 2);
+    assert!(game.read.is_read("main", "start", 2));
+}
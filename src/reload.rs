@@ -0,0 +1,268 @@
+//! Reloads the scripts and config files while the game is running when they are changed.
+//!
+//! This is meant to be used while the game is being made so that a writer can see their changes
+//! without restarting the game and getting back to the line that they were on.
+//! The files are checked every [`HotReload::interval`] and when one has changed it is loaded again
+//! and the line that was on screen is shown again, with any changes made to it.
+
+use std::{
+    fs,
+    path::PathBuf,
+    time::{
+        Duration,
+        Instant,
+        SystemTime,
+    },
+    collections::{
+        HashMap,
+        BTreeMap,
+    },
+};
+use toml;
+use piston_window::GfxFactory;
+
+use super::{
+    game::Game,
+    error::GameBuildError,
+    save::SaveData,
+    util::load_scripts_from_file,
+};
+
+/// The kinds of config files that can be reloaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConfigKind {
+    /// A GUI config file.
+    Gui,
+    /// A Character config file.
+    Characters,
+    /// A Background config file.
+    Backgrounds,
+    /// A Script config file. The scripts that it points to are also watched.
+    Scripts,
+    /// An Input config file.
+    Input,
+    /// A Music config file.
+    Music,
+    /// A Transition config file.
+    Transitions,
+}
+
+/// Watches config files for changes by checking when they were last modified.
+pub struct HotReload {
+    /// How often the files are checked.
+    pub interval: Duration,
+    /// The config files that are watched.
+    pub configs: Vec<(ConfigKind, PathBuf)>,
+    /// When each watched file was last modified.
+    /// ``None`` if the file couldn't be found.
+    modified: HashMap<PathBuf, Option<SystemTime>>,
+    /// When the files were last checked.
+    last_poll: Option<Instant>,
+}
+impl HotReload {
+    /// Create a new [`HotReload`] that isn't watching any files and checks them twice a second.
+    pub fn new() -> HotReload {
+        HotReload {
+            interval: Duration::from_millis(500),
+            configs: Vec::new(),
+            modified: HashMap::new(),
+            last_poll: None,
+        }
+    }
+    /// Starts watching a config file.
+    pub fn watch<P: Into<PathBuf>>(&mut self, kind: ConfigKind, path: P) {
+        let path = path.into();
+        self.configs.push((kind, path.clone()));
+        for file in files(kind, &path) {
+            let time = modified(&file);
+            self.modified.insert(file, time);
+        }
+    }
+    /// Checks the files if it is time to and returns the kinds of config files that have changed.
+    pub fn changed(&mut self) -> Vec<(ConfigKind, PathBuf)> {
+        let now = Instant::now();
+        match self.last_poll {
+            Some(last) if now.duration_since(last) < self.interval => return Vec::new(),
+            _ => self.last_poll = Some(now),
+        }
+        let mut changed = Vec::new();
+        for &(kind, ref config) in self.configs.iter() {
+            let mut is_changed = false;
+            // The scripts are found again every time as the Script config file may have changed.
+            for file in files(kind, config) {
+                let time = modified(&file);
+                if self.modified.insert(file, time) != Some(time) {
+                    is_changed = true;
+                }
+            }
+            if is_changed {
+                changed.push((kind, config.clone()));
+            }
+        }
+        changed
+    }
+}
+
+/// The files that are watched for a config file.
+fn files(kind: ConfigKind, config: &PathBuf) -> Vec<PathBuf> {
+    let mut files = vec![config.clone()];
+    if kind == ConfigKind::Scripts {
+        let scripts = fs::read_to_string(config).ok()
+            .and_then(|text| toml::from_str::<BTreeMap<String, String>>(&text).ok())
+            .unwrap_or_default();
+        files.extend(scripts.into_iter()
+            .filter(|&(ref k, _)| k.to_lowercase() != "default")
+            .map(|(_, v)| PathBuf::from(v)));
+    }
+    files
+}
+
+fn modified(path: &PathBuf) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl Game {
+    /// Starts watching a config file so that it is reloaded when it changes.
+    /// Use [`GameBuilder::hot_reload`](../util/struct.GameBuilder.html#method.hot_reload)
+    /// to watch all the config files that the game is built from.
+    pub fn watch_config<P: Into<PathBuf>>(&mut self, kind: ConfigKind, path: P) {
+        self.reload.get_or_insert_with(HotReload::new).watch(kind, path);
+    }
+    /// Reloads the config files that have changed and shows the line that was on screen again.
+    /// The player stays at the same step of the same anchor, or the last step if the anchor is
+    /// now shorter.
+    ///
+    /// This should be called every frame. Nothing happens if no files are being watched.
+    /// Returns ``true`` if anything was reloaded.
+    /// If a file couldn't be loaded then the old version is kept and the error is returned.
+    /// If the script that the player was on was removed then the story starts again from the default
+    /// script and [`GameBuildError::ScriptRemoved`] is returned.
+    pub fn hot_reload(&mut self, factory: &mut GfxFactory) -> Result<bool, GameBuildError> {
+        let changed = match self.reload {
+            Some(ref mut r) => r.changed(),
+            None => return Ok(false),
+        };
+        if changed.is_empty() {
+            return Ok(false)
+        }
+        let mut save = self.save_state();
+        let mut error = None;
+        for (kind, path) in changed {
+            let result = match kind {
+                ConfigKind::Gui => self.load_gui_from_file(&path).map_err(GameBuildError::Gui),
+                ConfigKind::Characters => self.load_characters_from_file(&path, factory)
+                    .map_err(GameBuildError::Characters),
+                ConfigKind::Backgrounds => self.load_backgrounds_from_file(&path, factory)
+                    .map_err(GameBuildError::Backgrounds),
+                ConfigKind::Scripts => load_scripts_from_file(&path)
+                    .map(|scripts| {
+                        // Scripts that were taken out of the Script config file are removed.
+                        self.story.scripts.retain(|name, _| scripts.map.contains_key(name));
+                        self.story.sources.retain(|name, _| scripts.map.contains_key(name));
+                        self.load_scripts(scripts);
+                    })
+                    .map_err(GameBuildError::Story),
+                ConfigKind::Input => self.load_input_from_file(&path).map_err(GameBuildError::Input),
+                ConfigKind::Music => match self.music {
+                    Some(ref mut m) => m.load_from_config_file(&path).map_err(GameBuildError::Music),
+                    None => Ok(()),
+                },
+                ConfigKind::Transitions => self.load_transitions_from_file(&path)
+                    .map_err(GameBuildError::Transition),
+            };
+            if let Err(e) = result {
+                error = error.or(Some(e));
+            }
+        }
+        let removed = self.keep_position(&mut save);
+        self.load_state(&save);
+        match (error, removed) {
+            (Some(e), _) => Err(e),
+            (None, Some(script)) => Err(GameBuildError::ScriptRemoved(script)),
+            (None, None) => Ok(true),
+        }
+    }
+    /// Keeps the player on the same line of a save after the scripts have been reloaded even if the
+    /// anchor has changed.
+    /// Returns the name of the script that the player was on if it was removed.
+    fn keep_position(&self, save: &mut SaveData) -> Option<String> {
+        let p = &mut save.position;
+        if let Some((i, j)) = self.story.find(&p.script, &p.anchor) {
+            let len = self.story.scripts.get_index(i)
+                .and_then(|(_, anchors)| anchors.get_index(j))
+                .map_or(0, |(_, steps)| steps.len());
+            p.step = p.step.min(len.saturating_sub(1));
+            return None
+        }
+        // The start of the script is used if the anchor was removed
+        // and the start of the default script is used if the whole script was removed.
+        let removed = !self.story.scripts.contains_key(&p.script);
+        let script = if removed {
+            self.story.default.as_ref()
+                .and_then(|d| self.story.scripts.get_full(d))
+                .map(|(_, name, anchors)| (name, anchors))
+                .or_else(|| self.story.scripts.get_index(0))
+        } else {
+            self.story.scripts.get_full(&p.script)
+                .map(|(_, name, anchors)| (name, anchors))
+        };
+        let old = p.script.clone();
+        if let Some((name, (anchor, _))) = script.and_then(|(name, anchors)| anchors.get_index(0).map(|a| (name, a))) {
+            p.script = name.to_string();
+            p.anchor = anchor.to_string();
+            p.step = 0;
+        }
+        if removed { Some(old) } else { None }
+    }
+}
+
+#[test]
+fn test_hot_reload_changed() {
+    use std::{
+        env,
+        io::Write,
+        fs::File,
+    };
+    let dir = env::temp_dir().join("gobu_reload_test");
+    fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("main.txt");
+    let config = dir.join("scripts.toml");
+    File::create(&script).unwrap().write_all(b"\"Hello\"").unwrap();
+    File::create(&config).unwrap()
+        .write_all(format!("main = {:?}\n", script.to_str().unwrap()).as_bytes()).unwrap();
+    let mut reload = HotReload::new();
+    reload.interval = Duration::from_millis(0);
+    reload.watch(ConfigKind::Scripts, &config);
+    assert!(reload.changed().is_empty());
+    // The script is saved again a minute later.
+    let later = modified(&script).unwrap() + Duration::from_secs(60);
+    fs::OpenOptions::new().write(true).open(&script).unwrap().set_modified(later).unwrap();
+    assert_eq!(reload.changed(), vec![(ConfigKind::Scripts, config.clone())]);
+    assert!(reload.changed().is_empty());
+}
+#[test]
+fn test_hot_reload_position() {
+    use super::save::Position;
+    let mut game = Game::new([800., 600.]);
+    game.story.load_from_str("main".to_string(), ":start\n\"One\"\n\"Two\"\n:end\nEND").unwrap();
+    game.story.load_from_str("other".to_string(), ":start\n\"Three\"\n\"Four\"\n\"Five\"").unwrap();
+    game.story.default = Some("other".to_string());
+    let mut save = game.save_state();
+    let mut position = |script: &str, anchor: &str, step: usize| {
+        save.position = Position {
+            script: script.to_string(),
+            anchor: anchor.to_string(),
+            step,
+        };
+        let removed = game.keep_position(&mut save);
+        (removed, save.position.script.clone(), save.position.anchor.clone(), save.position.step)
+    };
+    let s = |s: &str| s.to_string();
+    assert_eq!(position("other", "start", 2), (None, s("other"), s("start"), 2));
+    // The anchor is shorter.
+    assert_eq!(position("main", "start", 5), (None, s("main"), s("start"), 1));
+    // The anchor was removed.
+    assert_eq!(position("other", "middle", 1), (None, s("other"), s("start"), 0));
+    // The script was removed.
+    assert_eq!(position("gone", "start", 1), (Some(s("gone")), s("other"), s("start"), 0));
+}
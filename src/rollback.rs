@@ -0,0 +1,128 @@
+//! Going back to lines of dialogue that have already been shown.
+//!
+//! Each time the story stops on dialogue a snapshot of the game is recorded using the same
+//! [`SaveData`] that save slots use, so going back undoes everything that happened
+//! since that line, like characters being spawned, killed or moved and variables being set.
+
+use std::collections::VecDeque;
+
+use super::{
+    game::Game,
+    save::SaveData,
+};
+
+/// How many snapshots are kept by default.
+pub const DEFAULT_HISTORY_LIMIT: usize = 100;
+
+/// The snapshots of the game that can be gone back to.
+#[derive(Debug, Clone)]
+pub struct History {
+    /// The snapshots with the latest one at the back.
+    pub snapshots: VecDeque<SaveData>,
+    /// The most snapshots that are kept. The oldest ones are removed first.
+    pub limit: usize,
+}
+impl History {
+    /// Create a new [`History`] that keeps up to ``limit`` snapshots.
+    pub fn new(limit: usize) -> History {
+        History {
+            snapshots: VecDeque::new(),
+            limit,
+        }
+    }
+    /// Adds a snapshot to the history.
+    /// Nothing is added if it is the same as the latest snapshot.
+    pub fn push(&mut self, snapshot: SaveData) {
+        if self.limit == 0 || self.snapshots.back() == Some(&snapshot) {
+            return
+        }
+        self.snapshots.push_back(snapshot);
+        while self.snapshots.len() > self.limit {
+            self.snapshots.pop_front();
+        }
+    }
+    /// Removes all the snapshots.
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+}
+
+impl Game {
+    /// Records a snapshot of the game so that it can be gone back to.
+    /// This is done by the story every time it stops on dialogue.
+    pub fn record_history(&mut self) {
+        let snapshot = self.save_state();
+        self.history.push(snapshot);
+    }
+    /// Goes back to the previous line of dialogue.
+    ///
+    /// Returns ``false`` if there isn't anything to go back to.
+    pub fn rollback(&mut self) -> bool {
+        let current = self.save_state();
+        // If the latest snapshot is the line that is on screen, the one before it is gone back to.
+        let on_screen = match self.history.snapshots.back() {
+            Some(e) => e.position == current.position,
+            None => false,
+        };
+        if on_screen && self.history.snapshots.len() < 2 {
+            return false
+        }
+        if on_screen {
+            self.history.snapshots.pop_back();
+        }
+        match self.history.snapshots.pop_back() {
+            Some(snapshot) => {
+                self.load_state(&snapshot);
+                true
+            },
+            None => false,
+        }
+    }
+}
+
+#[test]
+fn test_history_limit() {
+    use std::collections::HashMap;
+    use super::save::{Position, SAVE_VERSION};
+    let snapshot = |step| SaveData {
+        version: SAVE_VERSION,
+        background: None,
+        music: None,
+        speaker: String::new(),
+        auto_advance: None,
+        position: Position { script: "main".to_string(), anchor: "start".to_string(), step },
+        calls: Vec::new(),
+        stage: Vec::new(),
+        variables: HashMap::new(),
+        backlog: 0,
+    };
+    let mut history = History::new(2);
+    history.push(snapshot(0));
+    history.push(snapshot(1));
+    history.push(snapshot(1));
+    history.push(snapshot(2));
+    assert_eq!(history.snapshots.len(), 2);
+    assert_eq!(history.snapshots[0].position.step, 1);
+}
+
+#[test]
+fn test_rollback_backlog() {
+    let mut game = Game::new([800., 600.]);
+    game.story.load_from_str("main".to_string(), r#"
+    :start
+        "Aiko": "One"
+        "Two"
+        "Three"
+    "#).unwrap();
+    game.story.set_script("main", None);
+    game.next_step();
+    game.next_step();
+    game.next_step();
+    assert_eq!(game.ui.backlog.lines.len(), 3);
+    assert!(game.rollback());
+    assert!(game.rollback());
+    let lines: Vec<&str> = game.ui.backlog.lines.iter().map(|l| l.1.as_str()).collect();
+    assert_eq!(lines, vec!["One"]);
+    game.next_step();
+    assert_eq!(game.ui.backlog.lines.len(), 2);
+}
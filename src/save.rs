@@ -0,0 +1,317 @@
+//! Saving and loading the state of the game.
+//!
+//! A save holds where the player is in the story, the characters on the stage,
+//! the background, the music and the variables of the story.
+//! Saves are written as TOML files into the save directory of the [`Game`]
+//! with one file for each slot (e.g. ``saves/slot_1.toml``).
+//!
+//! The scripts and anchors are saved using their names so that a save still works
+//! if more scripts are added to the game.
+
+use std::{
+    fs::{
+        self,
+        File,
+    },
+    io::{
+        Read,
+        Write,
+    },
+    path::PathBuf,
+    collections::HashMap,
+};
+use toml;
+
+use super::{
+    game::Game,
+    error::SaveError,
+    script::{
+        Value,
+        ScriptStep,
+    },
+};
+
+/// The version of the save format.
+/// Saves with a different version can't be loaded.
+pub const SAVE_VERSION: u32 = 1;
+
+/// Everything about the game that is kept in a save.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SaveData {
+    /// The version of the save format. See [`SAVE_VERSION`].
+    pub version: u32,
+    /// The name of the background.
+    pub background: Option<String>,
+    /// The name of the music that is playing.
+    pub music: Option<String>,
+    /// The speaker of the last dialogue before any variables are put in.
+    pub speaker: String,
+    /// How many seconds dialogue stays on screen before the story continues on its own.
+    pub auto_advance: Option<f64>,
+    /// How many lines had been added to the backlog before the step in ``position`` was shown.
+    #[serde(default)]
+    pub backlog: usize,
+    /// Where the player is in the story.
+    pub position: Position,
+    /// Where to go back to when a ``RETURN`` is reached. The last item is where the latest ``CALL`` came from.
+    // An empty list would be written as a value after the tables above it, which TOML doesn't allow.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<Position>,
+    /// The entities on the stage in the order that they are drawn.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stage: Vec<EntityData>,
+    /// The variables of the story.
+    pub variables: HashMap<String, Value>,
+}
+impl SaveData {
+    /// Reads a save from a [`&str`].
+    /// Returns an error if the save was made using a different version of the save format.
+    pub fn from_toml(text: &str) -> Result<SaveData, SaveError> {
+        #[derive(Deserialize)]
+        struct Version {
+            version: u32,
+        }
+        // The version is checked first so that an old save gives a clear error.
+        let version: Version = toml::from_str(text)?;
+        if version.version != SAVE_VERSION {
+            return Err(SaveError::Version(version.version))
+        }
+        Ok(toml::from_str(text)?)
+    }
+    /// Turns the save into a [`String`] using TOML.
+    pub fn to_toml(&self) -> Result<String, SaveError> {
+        Ok(toml::to_string(self)?)
+    }
+}
+
+/// A position in the story.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    /// The name of the script.
+    pub script: String,
+    /// The name of the anchor.
+    pub anchor: String,
+    /// The step in the anchor.
+    pub step: usize,
+}
+
+/// A [`CharacterEntity`](../character/struct.CharacterEntity.html) on the stage.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityData {
+    /// The name of the entity.
+    pub name: String,
+    /// The name of the character that the entity was spawned from.
+    pub character: String,
+    /// The state of the character that is being shown.
+    pub state: String,
+    /// The position of the entity on the screen.
+    /// [x, y]
+    pub pos: [f64; 2],
+    /// Whether the entity is visible.
+    pub visible: bool,
+}
+
+impl Game {
+    /// Gets the state of the game which can be saved.
+    ///
+    /// Any transitions that are happening are treated as if they have finished.
+    pub fn save_state(&self) -> SaveData {
+        let position = |index, step| {
+            let (script, anchor) = self.story.names(index).unwrap_or(("", ""));
+            Position {
+                script: script.to_string(),
+                anchor: anchor.to_string(),
+                step,
+            }
+        };
+        // The dialogue on screen is added to the backlog again when the save is loaded.
+        let on_screen = match self.story.script.get(self.story.current_step()) {
+            Some(&ScriptStep::Dialogue(..)) | Some(&ScriptStep::DialogueContinue(..)) => 1,
+            _ => 0,
+        }.min(self.ui.backlog.shown);
+        let stage = self.stage.iter()
+            .filter(|&(_, e)| !e.to_be_killed)
+            .map(|(k, e)| EntityData {
+                name: k.to_string(),
+                character: e.name.to_string(),
+                state: e.state.to_string(),
+                pos: [e.rect.pos.x, e.rect.pos.y],
+                visible: e.visible && !e.to_be_hidden,
+            })
+            .collect();
+        SaveData {
+            version: SAVE_VERSION,
+            background: self.background.name.clone(),
+            music: self.music.as_ref().and_then(|m| m.current.clone()),
+            speaker: self.story.speaker.clone(),
+            auto_advance: self.story.auto_advance,
+            position: position(self.story.index, self.story.current_step()),
+            calls: self.story.calls.iter()
+                .map(|&(script, anchor, step)| position((script, anchor), step))
+                .collect(),
+            stage,
+            variables: self.story.variables.clone(),
+            backlog: self.ui.backlog.shown - on_screen,
+        }
+    }
+    /// Sets the game to the state of a save.
+    ///
+    /// The step that was on screen when the game was saved is run again
+    /// so the dialogue or choice is shown to the player again.
+    pub fn load_state(&mut self, save: &SaveData) {
+        self.story.variables = save.variables.clone();
+        self.story.auto_advance = save.auto_advance;
+        self.story.choices.clear();
+        self.ui.choices.clear();
+        self.story.calls = save.calls.iter()
+            .filter_map(|p| self.story.find(&p.script, &p.anchor).map(|(i, j)| (i, j, p.step)))
+            .collect();
+        self.stage.clear();
+        for entity in save.stage.iter() {
+            let name = &entity.name;
+            if self.add_to_stage(name.to_string(), entity.character.to_string()) {
+                self.change_entity_state(name, &entity.state);
+                if let Some(e) = self.stage.get_mut(name) {
+                    e.set_pos(entity.pos.into());
+                    e.set_visible(entity.visible);
+                }
+            }
+        }
+        match save.background {
+            Some(ref bg) => self.set_background(bg),
+            None => self.background.clear_texture(),
+        }
+        if let Some(ref mut m) = self.music {
+            if m.current != save.music {
+                match save.music {
+                    Some(ref track) => {
+                        let _ = m.set_music(track);
+                    },
+                    None => m.stop(0.),
+                }
+            }
+        }
+        self.story.speaker = save.speaker.clone();
+        if let Some(ref mut e) = self.ui.speaker_box {
+            e.set_text(self.story.interpolate(&save.speaker));
+        }
+        self.ui.backlog.truncate(save.backlog);
+        let p = &save.position;
+        let index = self.story.find(&p.script, &p.anchor).unwrap_or((usize::max_value(), 0));
+        self.story.set_position(index, p.step);
+        self.next_step();
+    }
+    /// The path of the file that a slot is saved to.
+    pub fn slot_path(&self, slot: u32) -> PathBuf {
+        self.save_dir.join(format!("slot_{}.toml", slot))
+    }
+    /// Saves the game into a numbered slot, replacing what was there before.
+    pub fn save_to_slot(&self, slot: u32) -> Result<(), SaveError> {
+        let text = self.save_state().to_toml()?;
+        fs::create_dir_all(&self.save_dir)?;
+        File::create(self.slot_path(slot))?.write_all(text.as_bytes())?;
+        Ok(())
+    }
+    /// Loads the game from a numbered slot.
+    pub fn load_from_slot(&mut self, slot: u32) -> Result<(), SaveError> {
+        let mut buffer = String::new();
+        File::open(self.slot_path(slot))?.read_to_string(&mut buffer)?;
+        let save = SaveData::from_toml(&buffer)?;
+        // The lines before the save can't be gone back to.
+        self.history.clear();
+        self.ui.backlog.clear();
+        self.load_state(&save);
+        Ok(())
+    }
+    /// Whether there is a save in the slot.
+    pub fn slot_exists(&self, slot: u32) -> bool {
+        self.slot_path(slot).is_file()
+    }
+}
+
+#[test]
+fn test_save_data() {
+    let entity = |name: &str, pos| EntityData {
+        name: name.to_string(),
+        character: "Aiko".to_string(),
+        state: "Happy".to_string(),
+        pos,
+        visible: true,
+    };
+    // The entities are in the order that they are drawn so the one in front has to stay last.
+    let stage = vec![entity("Behind", [120., 40.]), entity("Front", [100., 40.])];
+    let mut variables = HashMap::new();
+    variables.insert("coins".to_string(), Value::Number(3.));
+    variables.insert("player".to_string(), Value::Text("Hiro".to_string()));
+    variables.insert("met_aiko".to_string(), Value::Bool(true));
+    let save = SaveData {
+        version: SAVE_VERSION,
+        background: Some("park".to_string()),
+        music: None,
+        speaker: "[player]".to_string(),
+        auto_advance: None,
+        position: Position { script: "main".to_string(), anchor: "park".to_string(), step: 4 },
+        calls: vec![Position { script: "main".to_string(), anchor: "start".to_string(), step: 2 }],
+        stage,
+        variables,
+        backlog: 12,
+    };
+    let text = save.to_toml().unwrap();
+    assert_eq!(SaveData::from_toml(&text).unwrap(), save);
+    match SaveData::from_toml(&text.replace("version = 1", "version = 0")) {
+        Err(SaveError::Version(0)) => {},
+        e => panic!("Expected a version error but got {:?}", e),
+    }
+}
+
+#[test]
+fn test_save_state() {
+    let new_game = || {
+        let mut game = Game::new([800., 600.]);
+        game.story.load_from_str("main".to_string(), r#"
+        :start
+            SET coins = 3
+            "Good morning."
+            CALL routines:morning_routine
+            "Off to school."
+            END
+        "#).unwrap();
+        game.story.load_from_str("routines".to_string(), r#"
+        :morning_routine
+            CALL routines:breakfast
+            "Get dressed."
+            RETURN
+        :breakfast
+            "Eat toast."
+            RETURN
+        "#).unwrap();
+        game
+    };
+    let text = |game: &Game| game.ui.backlog.lines.back().map(|l| l.1.clone()).unwrap_or_default();
+    let mut game = new_game();
+    game.story.set_script("main", None);
+    game.next_step();
+    game.next_step();
+    assert_eq!(text(&game), "Eat toast.");
+    let save = game.save_state();
+    let position = |script: &str, anchor: &str, step| Position {
+        script: script.to_string(),
+        anchor: anchor.to_string(),
+        step,
+    };
+    // The CALL that was made first is returned to last.
+    assert_eq!(save.calls, vec![position("main", "start", 3), position("routines", "morning_routine", 1)]);
+    let save = SaveData::from_toml(&save.to_toml().unwrap()).unwrap();
+    let mut game = new_game();
+    game.load_state(&save);
+    assert_eq!(text(&game), "Eat toast.");
+    assert_eq!(game.save_state(), save);
+    game.next_step();
+    assert_eq!(text(&game), "Get dressed.");
+    game.next_step();
+    assert_eq!(text(&game), "Off to school.");
+    assert_eq!(game.story.variables["coins"], Value::Number(3.));
+    let save = game.save_state();
+    assert!(save.calls.is_empty());
+    assert_eq!(SaveData::from_toml(&save.to_toml().unwrap()).unwrap(), save);
+}
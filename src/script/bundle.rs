@@ -0,0 +1,158 @@
+//! Script bundles which are scripts that have already been parsed and written in a binary format.
+//!
+//! Loading a bundle is much faster than parsing every script when the game starts and the
+//! scripts aren't shipped as text.
+//!
+//! A bundle starts with ``GOBU`` and the version of the bundle format, followed by the scripts.
+//! A bundle made with a different version of the format can't be loaded and has to be compiled again.
+
+use std::{
+    fs::File,
+    io::{
+        Read,
+        Write,
+    },
+    path::Path,
+    collections::HashMap,
+};
+use bincode;
+use indexmap::IndexMap;
+
+use super::{
+    Script,
+    ScriptStep,
+};
+use super::super::{
+    error::BundleError,
+    util::load::{
+        load_scripts_from_file,
+        ScriptsFromFile,
+    },
+};
+
+/// The bytes that every bundle starts with.
+pub const BUNDLE_MAGIC: &[u8; 4] = b"GOBU";
+/// The version of the bundle format.
+/// This changes whenever [`ScriptStep`] changes.
+pub const BUNDLE_VERSION: u32 = 6;
+
+/// What is written into a bundle.
+/// [`IndexMap`] is written as a list so that the order of the scripts and anchors is kept.
+#[derive(Serialize, Deserialize)]
+struct BundleData {
+    default: Option<String>,
+    scripts: Vec<(String, Vec<(String, Vec<ScriptStep>)>)>,
+}
+
+/// Turns scripts into a bundle.
+pub fn write_bundle(scripts: &ScriptsFromFile) -> Result<Vec<u8>, BundleError> {
+    let data = BundleData {
+        default: scripts.default.clone(),
+        scripts: scripts.map.iter()
+            .map(|(name, anchors)| (name.to_string(), anchors.iter()
+                .map(|(anchor, steps)| (anchor.to_string(), steps.clone()))
+                .collect()))
+            .collect(),
+    };
+    let mut bytes = BUNDLE_MAGIC.to_vec();
+    bytes.extend_from_slice(&u32_to_bytes(BUNDLE_VERSION));
+    bytes.extend(bincode::serialize(&data)?);
+    Ok(bytes)
+}
+
+/// Reads the scripts from a bundle.
+pub fn read_bundle(bytes: &[u8]) -> Result<ScriptsFromFile, BundleError> {
+    if bytes.len() < 8 || &bytes[..4] != BUNDLE_MAGIC {
+        return Err(BundleError::NotABundle)
+    }
+    let version = bytes_to_u32(&bytes[4..8]);
+    if version != BUNDLE_VERSION {
+        return Err(BundleError::Version(version))
+    }
+    let data: BundleData = bincode::deserialize(&bytes[8..])?;
+    Ok(ScriptsFromFile {
+        default: data.default,
+        map: data.scripts.into_iter()
+            .map(|(name, anchors)| (name, anchors.into_iter().collect::<IndexMap<_, _>>()))
+            .collect(),
+        // The lines of the steps aren't kept as the script files aren't shipped with the game.
+        sources: HashMap::new(),
+    })
+}
+
+/// Parses the scripts in a Script config file and writes them into a bundle file.
+pub fn compile_bundle<P: AsRef<Path>, Q: AsRef<Path>>(config: P, bundle: Q) -> Result<(), BundleError> {
+    let scripts = load_scripts_from_file(config)?;
+    File::create(bundle)?.write_all(&write_bundle(&scripts)?)?;
+    Ok(())
+}
+
+fn u32_to_bytes(n: u32) -> [u8; 4] {
+    [n as u8, (n >> 8) as u8, (n >> 16) as u8, (n >> 24) as u8]
+}
+
+fn bytes_to_u32(b: &[u8]) -> u32 {
+    b[0] as u32 | (b[1] as u32) << 8 | (b[2] as u32) << 16 | (b[3] as u32) << 24
+}
+
+impl Script {
+    /// Loads the scripts from a bundle that is in memory, like one from ``include_bytes!``.
+    /// If the bundle has a default script then the story is set to it.
+    pub fn load_bundle(&mut self, bytes: &[u8]) -> Result<(), BundleError> {
+        let scripts = read_bundle(bytes)?;
+        self.load_scripts(scripts.map);
+        if let Some(e) = scripts.default {
+            self.set_script(&e, None);
+            self.default = Some(e);
+        }
+        Ok(())
+    }
+    /// Loads the scripts from a bundle file.
+    /// If the bundle has a default script then the story is set to it.
+    pub fn load_bundle_from_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), BundleError> {
+        let mut buffer = Vec::new();
+        File::open(path)?.read_to_end(&mut buffer)?;
+        self.load_bundle(&buffer)
+    }
+}
+
+#[test]
+fn test_bundle() {
+    use super::parser::translate;
+    let mut map = IndexMap::new();
+    map.insert("main".to_string(), translate(r#"
+    :start
+        SET met = met + 1
+        IF met >= 2 AND name == "Aiko"
+            "Aiko": "Hello again, [name]!"
+        ENDIF
+        SPAWN 'Aiko' as 'A' at (1.0, 2.0) with 'FadeIn'
+        AUTO 1.5
+        CHOICE
+            "Stay" -> :start
+            "Leave" -> other:
+        ENDCHOICE
+    :end
+        END
+    "#).unwrap());
+    let scripts = ScriptsFromFile {
+        default: Some("main".to_string()),
+        map,
+        sources: HashMap::new(),
+    };
+    let bytes = write_bundle(&scripts).unwrap();
+    let read = read_bundle(&bytes).unwrap();
+    assert_eq!(read.default, scripts.default);
+    assert_eq!(read.map, scripts.map);
+    assert_eq!(read.map["main"].keys().collect::<Vec<_>>(), vec!["start", "end"]);
+    let mut old = bytes.clone();
+    old[4] = 0;
+    match read_bundle(&old) {
+        Err(BundleError::Version(0)) => {},
+        e => panic!("{:?}", e.map(|s| s.map)),
+    }
+    match read_bundle(b"not a bundle") {
+        Err(BundleError::NotABundle) => {},
+        e => panic!("{:?}", e.map(|s| s.map)),
+    }
+}
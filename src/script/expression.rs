@@ -0,0 +1,200 @@
+//! The variables that are stored by the story and the expressions that use them.
+//!
+//! Variables are set using ``SET`` and are read by the conditions of ``IF``.
+
+use std::{
+    fmt,
+    collections::HashMap,
+};
+use serde::{
+    Serialize,
+    Serializer,
+    Deserialize,
+    Deserializer,
+};
+
+/// A value that a variable can hold.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Value {
+    /// A number like ``3`` or ``0.5``.
+    Number(f64),
+    /// Either ``true`` or ``false``.
+    Bool(bool),
+    /// Text like ``"Aiko"``.
+    Text(String),
+}
+impl Value {
+    /// Whether the value counts as true in a condition.
+    /// Numbers are true if they are not 0 and text is true if it is not empty.
+    pub fn is_true(&self) -> bool {
+        match *self {
+            Value::Number(n) => n != 0.,
+            Value::Bool(b) => b,
+            Value::Text(ref t) => !t.is_empty(),
+        }
+    }
+    /// Turns the value into a number.
+    /// Text that isn't a number becomes 0.
+    pub fn to_number(&self) -> f64 {
+        match *self {
+            Value::Number(n) => n,
+            Value::Bool(b) => if b { 1. } else { 0. },
+            Value::Text(ref t) => t.trim().parse().unwrap_or(0.),
+        }
+    }
+}
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            // Whole numbers are shown without a decimal point.
+            Value::Number(n) if n.fract() == 0. && n.is_finite() => write!(f, "{}", n as i64),
+            Value::Number(n) => write!(f, "{}", n),
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Text(ref t) => write!(f, "{}", t),
+        }
+    }
+}
+
+/// An operator that combines two expressions.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Operator {
+    /// ``+`` Adds numbers or joins text together.
+    Add,
+    /// ``-``
+    Subtract,
+    /// ``*``
+    Multiply,
+    /// ``/``
+    Divide,
+    /// ``%``
+    Remainder,
+    /// ``==``
+    Equal,
+    /// ``!=``
+    NotEqual,
+    /// ``<``
+    Less,
+    /// ``<=``
+    LessEqual,
+    /// ``>``
+    Greater,
+    /// ``>=``
+    GreaterEqual,
+    /// ``AND``
+    And,
+    /// ``OR``
+    Or,
+}
+
+/// An expression that is turned into a [`Value`] when the script is run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Expression {
+    /// A value that is written in the script.
+    Value(#[serde(with = "tagged_value")] Value),
+    /// The value of a variable. A variable that hasn't been set is 0.
+    Variable(String),
+    /// ``NOT`` an expression.
+    Not(Box<Expression>),
+    /// The negative of an expression.
+    Negate(Box<Expression>),
+    /// Two expressions combined using an operator.
+    /// (Left, Operator, Right)
+    Binary(Box<Expression>, Operator, Box<Expression>),
+}
+impl Expression {
+    /// Turns the expression into a [`Value`] using the variables provided.
+    pub fn evaluate(&self, variables: &HashMap<String, Value>) -> Value {
+        match *self {
+            Expression::Value(ref v) => v.clone(),
+            Expression::Variable(ref name) => variables.get(name)
+                .cloned()
+                .unwrap_or(Value::Number(0.)),
+            Expression::Not(ref e) => Value::Bool(!e.evaluate(variables).is_true()),
+            Expression::Negate(ref e) => Value::Number(-e.evaluate(variables).to_number()),
+            // AND and OR only evaluate the right side if they need to.
+            Expression::Binary(ref left, Operator::And, ref right) => {
+                Value::Bool(left.evaluate(variables).is_true() && right.evaluate(variables).is_true())
+            },
+            Expression::Binary(ref left, Operator::Or, ref right) => {
+                Value::Bool(left.evaluate(variables).is_true() || right.evaluate(variables).is_true())
+            },
+            Expression::Binary(ref left, op, ref right) => {
+                apply(left.evaluate(variables), op, right.evaluate(variables))
+            },
+        }
+    }
+}
+
+fn apply(left: Value, op: Operator, right: Value) -> Value {
+    use self::Operator::*;
+    use std::cmp::Ordering;
+    let order = match (&left, &right) {
+        (&Value::Number(a), &Value::Number(b)) => a.partial_cmp(&b),
+        (&Value::Text(ref a), &Value::Text(ref b)) => Some(a.cmp(b)),
+        (&Value::Bool(a), &Value::Bool(b)) => Some(a.cmp(&b)),
+        // Values of different types are never equal.
+        _ => None,
+    };
+    match op {
+        Add => match (&left, &right) {
+            (&Value::Text(_), _) | (_, &Value::Text(_)) => Value::Text(format!("{}{}", left, right)),
+            _ => Value::Number(left.to_number() + right.to_number()),
+        },
+        Subtract => Value::Number(left.to_number() - right.to_number()),
+        Multiply => Value::Number(left.to_number() * right.to_number()),
+        Divide => Value::Number(left.to_number() / right.to_number()),
+        Remainder => Value::Number(left.to_number() % right.to_number()),
+        Equal => Value::Bool(order == Some(Ordering::Equal)),
+        NotEqual => Value::Bool(order != Some(Ordering::Equal)),
+        Less => Value::Bool(order == Some(Ordering::Less)),
+        LessEqual => Value::Bool(order == Some(Ordering::Less) || order == Some(Ordering::Equal)),
+        Greater => Value::Bool(order == Some(Ordering::Greater)),
+        GreaterEqual => Value::Bool(order == Some(Ordering::Greater) || order == Some(Ordering::Equal)),
+        // AND and OR are worked out by evaluate so that the right side isn't always evaluated.
+        And | Or => unreachable!(),
+    }
+}
+
+/// Writes a [`Value`] with the kind of value it is.
+/// [`Value`] is untagged so that it looks nice in saves but that can't be read back from
+/// formats that don't say what kind of value is next, like script bundles.
+mod tagged_value {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    enum Tagged {
+        Number(f64),
+        Bool(bool),
+        Text(String),
+    }
+
+    pub fn serialize<S: Serializer>(value: &Value, serializer: S) -> Result<S::Ok, S::Error> {
+        match *value {
+            Value::Number(n) => Tagged::Number(n),
+            Value::Bool(b) => Tagged::Bool(b),
+            Value::Text(ref t) => Tagged::Text(t.to_string()),
+        }.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Value, D::Error> {
+        Ok(match Tagged::deserialize(deserializer)? {
+            Tagged::Number(n) => Value::Number(n),
+            Tagged::Bool(b) => Value::Bool(b),
+            Tagged::Text(t) => Value::Text(t),
+        })
+    }
+}
+
+#[test]
+fn test_evaluate() {
+    let mut variables = HashMap::new();
+    variables.insert("affection".to_string(), Value::Number(2.));
+    let expr = Expression::Binary(
+        Box::new(Expression::Variable("affection".to_string())),
+        Operator::Add,
+        Box::new(Expression::Value(Value::Number(1.))),
+    );
+    assert_eq!(expr.evaluate(&variables), Value::Number(3.));
+    assert_eq!(Expression::Variable("unknown".to_string()).evaluate(&variables), Value::Number(0.));
+}
\ No newline at end of file
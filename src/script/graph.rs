@@ -0,0 +1,311 @@
+//! Turns the scripts into a graph of how the story flows so that it can be looked at.
+//!
+//! Every anchor of every script is a node. The edges are where the story can go from that anchor:
+//! * ``->``, ``CALL`` and the options of a ``CHOICE``
+//! * Falling through to the next anchor of the script (or the end if it is the last one)
+//! * ``END``
+//!
+//! The graph can be written as [Graphviz](https://graphviz.org) DOT or as [Mermaid](https://mermaid.js.org)
+//! and the anchors that can never be reached from the start are highlighted.
+
+use std::{
+    fmt::Write,
+    collections::HashSet,
+};
+
+use super::{
+    Script,
+    ScriptStep,
+};
+
+/// The kind of step that an edge comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    /// A ``->``.
+    GoTo,
+    /// A ``CALL``.
+    Call,
+    /// An option of a ``CHOICE`` with its text.
+    Choice(String),
+    /// The end of the anchor was reached so the story goes to the next anchor.
+    FallThrough,
+}
+
+/// Where an edge goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Target {
+    /// The index of an anchor in [`StoryGraph::nodes`].
+    Anchor(usize),
+    /// The end of the story.
+    End,
+}
+
+/// A graph of how the story flows between the anchors of the scripts.
+#[derive(Debug, Clone)]
+pub struct StoryGraph {
+    /// Every anchor of every script.
+    /// (Script, Anchor)
+    pub nodes: Vec<(String, String)>,
+    /// Where the story can go from each anchor.
+    /// (From, To, Kind)
+    pub edges: Vec<(usize, Target, EdgeKind)>,
+    /// Whether each anchor can be reached from the start.
+    pub reached: Vec<bool>,
+    /// The anchor that the story starts from.
+    pub start: Option<usize>,
+}
+impl StoryGraph {
+    /// Writes the graph using the Graphviz DOT language.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        out.push_str("digraph story {\n    node [shape=box];\n");
+        for (i, &(ref script, ref anchor)) in self.nodes.iter().enumerate() {
+            let mut attrs = format!("label=\"{}\"", dot_escape(&format!("{}:{}", script, anchor)));
+            if !self.reached[i] {
+                attrs.push_str(", style=filled, fillcolor=\"#ffcccc\", color=\"#cc0000\"");
+            } else if self.start == Some(i) {
+                attrs.push_str(", penwidth=2");
+            }
+            let _ = writeln!(out, "    n{} [{}];", i, attrs);
+        }
+        if self.edges.iter().any(|e| e.1 == Target::End) {
+            out.push_str("    end [label=\"END\", shape=doublecircle];\n");
+        }
+        for &(from, to, ref kind) in self.edges.iter() {
+            let attrs = match *kind {
+                EdgeKind::GoTo => String::new(),
+                EdgeKind::Call => " [label=\"CALL\"]".to_string(),
+                EdgeKind::Choice(ref text) => format!(" [label=\"{}\"]", dot_escape(text)),
+                EdgeKind::FallThrough => " [style=dashed]".to_string(),
+            };
+            let _ = writeln!(out, "    n{} -> {}{};", from, target_id(to), attrs);
+        }
+        out.push_str("}\n");
+        out
+    }
+    /// Writes the graph as a Mermaid flowchart.
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::new();
+        out.push_str("graph TD\n");
+        for (i, &(ref script, ref anchor)) in self.nodes.iter().enumerate() {
+            let _ = writeln!(out, "    n{}[\"{}\"]", i, mermaid_escape(&format!("{}:{}", script, anchor)));
+        }
+        if self.edges.iter().any(|e| e.1 == Target::End) {
+            // "end" is a keyword in Mermaid.
+            out.push_str("    end_node((END))\n");
+        }
+        for &(from, to, ref kind) in self.edges.iter() {
+            let to = match to {
+                Target::End => "end_node".to_string(),
+                to => target_id(to),
+            };
+            let _ = match *kind {
+                EdgeKind::GoTo => writeln!(out, "    n{} --> {}", from, to),
+                EdgeKind::Call => writeln!(out, "    n{} -->|CALL| {}", from, to),
+                EdgeKind::Choice(ref text) => writeln!(out, "    n{} -->|\"{}\"| {}", from, mermaid_escape(text), to),
+                EdgeKind::FallThrough => writeln!(out, "    n{} -.-> {}", from, to),
+            };
+        }
+        let unreached: Vec<String> = (0..self.nodes.len())
+            .filter(|&i| !self.reached[i])
+            .map(|i| format!("n{}", i))
+            .collect();
+        if !unreached.is_empty() {
+            out.push_str("    classDef unreached fill:#ffcccc,stroke:#cc0000\n");
+            let _ = writeln!(out, "    class {} unreached", unreached.join(","));
+        }
+        out
+    }
+}
+
+/// The name of the node that an edge goes to.
+fn target_id(target: Target) -> String {
+    match target {
+        Target::Anchor(i) => format!("n{}", i),
+        Target::End => "end".to_string(),
+    }
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+impl Script {
+    /// Creates a graph of how the story flows starting from a script and maybe an anchor.
+    /// If the anchor is ``None`` then the first anchor of the script is used.
+    pub fn graph(&self, script: &str, anchor: Option<&str>) -> StoryGraph {
+        // The index of the first node of each script.
+        let mut offsets = Vec::new();
+        let mut nodes = Vec::new();
+        for (name, anchors) in self.scripts.iter() {
+            offsets.push(nodes.len());
+            nodes.extend(anchors.keys().map(|a| (name.to_string(), a.to_string())));
+        }
+        let mut edges = Vec::new();
+        for (i, (name, anchors)) in self.scripts.iter().enumerate() {
+            for (j, (_, steps)) in anchors.iter().enumerate() {
+                let from = offsets[i] + j;
+                for (to, kind) in self.anchor_edges(name, steps) {
+                    let to = match to {
+                        // Falls through to the next anchor which is the end if it is the last one.
+                        None => if j + 1 < anchors.len() { Target::Anchor(from + 1) } else { Target::End },
+                        Some(to) => to.map_or(Target::End, |(a, b)| Target::Anchor(offsets[a] + b)),
+                    };
+                    if !edges.contains(&(from, to, kind.clone())) {
+                        edges.push((from, to, kind));
+                    }
+                }
+            }
+        }
+        let start = self.scripts.get_full(script)
+            .and_then(|(i, _, anchors)| match anchor {
+                Some(a) => anchors.get_full(a).map(|(j, _, _)| offsets[i] + j),
+                None if !anchors.is_empty() => Some(offsets[i]),
+                None => None,
+            });
+        let mut reached = vec![false; nodes.len()];
+        let mut stack: Vec<usize> = start.into_iter().collect();
+        while let Some(node) = stack.pop() {
+            if reached[node] {
+                continue
+            }
+            reached[node] = true;
+            for &(from, to, _) in edges.iter() {
+                if let (true, Target::Anchor(to)) = (from == node, to) {
+                    stack.push(to);
+                }
+            }
+        }
+        StoryGraph {
+            nodes,
+            edges,
+            reached,
+            start,
+        }
+    }
+    /// Follows the steps of an anchor in ``script`` and finds where the story can go from it.
+    /// A target of ``None`` means falling through to the next anchor and ``Some(None)`` is the end.
+    fn anchor_edges(&self, script: &str, steps: &[ScriptStep])
+        -> Vec<(Option<Option<(usize, usize)>>, EdgeKind)> {
+        use self::ScriptStep::*;
+        let mut edges = Vec::new();
+        let mut seen = HashSet::new();
+        let mut stack = vec![0];
+        while let Some(i) = stack.pop() {
+            if !seen.insert(i) {
+                continue
+            }
+            match steps.get(i) {
+                None => edges.push((None, EdgeKind::FallThrough)),
+                Some(&GoTo(ref s, ref a)) => edges.push((Some(self.target(script, s, a)), EdgeKind::GoTo)),
+                Some(&Call(ref s, ref a)) => {
+                    edges.push((Some(self.target(script, s, a)), EdgeKind::Call));
+                    stack.push(i + 1);
+                },
+                Some(&Choice(ref options)) => for &(ref text, ref s, ref a) in options {
+                    edges.push((Some(self.target(script, s, a)), EdgeKind::Choice(text.to_string())));
+                },
+                Some(&If(_, skip)) => {
+                    stack.push(i + 1 + skip);
+                    stack.push(i + 1);
+                },
+                Some(&Else(skip)) => stack.push(i + 1 + skip),
+                Some(&End) => edges.push((Some(None), EdgeKind::GoTo)),
+                // Goes back to where the CALL came from which is already followed.
+                Some(&Return) => {},
+                Some(_) => stack.push(i + 1),
+            }
+        }
+        edges
+    }
+    /// The index of the script and anchor that the story ends up at when it goes to a target,
+    /// the same way as [`Script::go_to`]. ``None`` is the end of the story.
+    fn target(&self, current: &str, script: &Option<String>, anchor: &Option<String>) -> Option<(usize, usize)> {
+        let name = script.as_ref().map(|s| s.as_str()).unwrap_or(current);
+        let i = match self.scripts.get_full(name) {
+            Some((i, _, anchors)) => {
+                let j = match *anchor {
+                    Some(ref a) => anchors.get_full(a).map(|(j, _, _)| j),
+                    None if !anchors.is_empty() => Some(0),
+                    None => None,
+                };
+                if let Some(j) = j {
+                    return Some((i, j))
+                }
+                i
+            },
+            None => 0,
+        };
+        // A script or anchor that doesn't exist leaves the story on an empty anchor at the start of the
+        // script (or the first script), so it falls through to the second anchor of that script.
+        self.scripts.get_index(i)
+            .filter(|&(_, anchors)| anchors.len() > 1)
+            .map(|_| (i, 1))
+    }
+}
+
+#[test]
+fn test_graph() {
+    let mut story = Script::new();
+    story.load_from_str("main".to_string(), r#"
+    :start
+        "Hello!"
+        CHOICE
+            "Park" -> :park
+            "Home" -> :home
+        ENDCHOICE
+    :park
+        IF met == 1
+            -> :home
+        ENDIF
+    :home
+        END
+    :secret
+        "Nobody comes here."
+    "#).unwrap();
+    let graph = story.graph("main", None);
+    assert_eq!(graph.nodes.len(), 4);
+    assert_eq!(graph.reached, vec![true, true, true, false]);
+    assert!(graph.edges.contains(&(0, Target::Anchor(1), EdgeKind::Choice("Park".to_string()))));
+    assert!(graph.edges.contains(&(1, Target::Anchor(2), EdgeKind::GoTo)));
+    assert!(graph.edges.contains(&(1, Target::Anchor(2), EdgeKind::FallThrough)));
+    assert!(graph.edges.contains(&(2, Target::End, EdgeKind::GoTo)));
+    assert!(graph.edges.contains(&(3, Target::End, EdgeKind::FallThrough)));
+    assert!(graph.to_dot().contains("n3 [label=\"main:secret\", style=filled"));
+    assert!(graph.to_mermaid().contains("class n3 unreached"));
+}
+#[test]
+fn test_graph_unknown_target() {
+    use super::headless::Headless;
+    let mut story = Headless::new();
+    story.load_script("main", r#"
+    :start
+        -> nowhere:start
+    :second
+        "Fell through."
+        -> :missing
+    :third
+        END
+    "#).unwrap();
+    story.load_script("other", r#"
+    :only
+        -> :missing
+    "#).unwrap();
+    let graph = story.story.graph("main", None);
+    // Both go to where the story actually carries on from.
+    assert!(graph.edges.contains(&(0, Target::Anchor(1), EdgeKind::GoTo)));
+    assert!(graph.edges.contains(&(1, Target::Anchor(1), EdgeKind::GoTo)));
+    // There isn't an anchor after the first one of "other" so the story ends.
+    assert!(graph.edges.contains(&(3, Target::End, EdgeKind::GoTo)));
+    assert_eq!(graph.reached, vec![true, true, false, false]);
+    story.start("main", None);
+    assert_eq!(story.text, "Fell through.");
+    story.next();
+    assert_eq!(story.text, "Fell through.");
+    story.start("other", None);
+    assert!(story.is_ended());
+}
@@ -0,0 +1,425 @@
+//! A story runtime that doesn't draw or play anything so stories can be tested using ``cargo test``.
+//!
+//! Characters, backgrounds, music, sound effects and voice lines are only known by their names and everything that
+//! happens is recorded so it can be checked.
+//!
+//! ```rust
+//! use gobu::script::headless::Headless;
+//!
+//! let mut story = Headless::new();
+//! story.add_character("Aiko", "happy", &["sad"]);
+//! story.load_script("main", r#"
+//! :start
+//!     SPAWN 'Aiko'
+//!     "Aiko": "Hello!"
+//!     SHOW 'Aiko' ~ 'sad'
+//!     "Aiko": "Goodbye..."
+//!     END
+//! "#).unwrap();
+//! story.start("main", None);
+//! assert_eq!(story.text, "Hello!");
+//! story.next();
+//! assert_eq!(story.stage["Aiko"].state, "sad");
+//! story.next();
+//! assert!(story.is_ended());
+//! ```
+
+use std::collections::{
+    BTreeMap,
+    HashMap,
+    HashSet,
+};
+
+use super::{
+    Script,
+    runtime::{
+        self,
+        Runtime,
+    },
+};
+use super::super::{
+    error::ScriptImportError,
+    music::volume::{
+        Channel,
+        Volume,
+    },
+};
+
+/// An entity on the stage of a [`Headless`] story.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeadlessEntity {
+    /// The name of the character that the entity was spawned from.
+    pub character: String,
+    /// The state of the character that is being shown.
+    pub state: String,
+    /// The position on the grid that the entity was last moved to.
+    pub pos: Option<(f64, f64)>,
+    /// Whether the entity is visible.
+    pub visible: bool,
+    /// The last transition that was applied to the entity.
+    pub transition: Option<String>,
+}
+
+/// Runs a story without drawing or playing anything, recording what the player would see and hear.
+///
+/// Like [`Game`](../../game/struct.Game.html), characters, states, backgrounds, music, sound effects and voice lines that
+/// haven't been added are ignored by the steps that use them.
+pub struct Headless {
+    /// The story that is being run.
+    pub story: Script,
+    /// The characters that can be spawned and their states.
+    /// Character -> (DefaultState, States)
+    pub characters: HashMap<String, (String, HashSet<String>)>,
+    /// The names of the backgrounds that can be set.
+    pub backgrounds: HashSet<String>,
+    /// The names of the music that can be played.
+    pub music_library: HashSet<String>,
+    /// The names of the sound effects that can be played.
+    pub sfx_library: HashSet<String>,
+    /// The names of the voice lines that can be played.
+    pub voice_library: HashSet<String>,
+    /// The entities on the stage.
+    pub stage: BTreeMap<String, HeadlessEntity>,
+    /// The name of the background.
+    pub background: Option<String>,
+    /// The name of the music that is playing.
+    pub music: Option<String>,
+    /// Every sound effect that has been played.
+    pub sounds: Vec<String>,
+    /// The name of the voice line of the dialogue on screen.
+    pub voice: Option<String>,
+    /// The volume of each channel of audio.
+    pub volume: Volume,
+    /// The speaker of the dialogue on screen.
+    pub speaker: String,
+    /// The dialogue on screen.
+    pub text: String,
+    /// The options of the choice that the player has to make.
+    pub choices: Vec<String>,
+    /// Every line of dialogue that has been shown.
+    /// (Speaker, Text)
+    pub log: Vec<(String, String)>,
+}
+impl Headless {
+    /// Create a new [`Headless`] story without any scripts or assets.
+    pub fn new() -> Headless {
+        Headless {
+            story: Script::new(),
+            characters: HashMap::new(),
+            backgrounds: HashSet::new(),
+            music_library: HashSet::new(),
+            sfx_library: HashSet::new(),
+            voice_library: HashSet::new(),
+            stage: BTreeMap::new(),
+            background: None,
+            music: None,
+            sounds: Vec::new(),
+            voice: None,
+            volume: Volume::new(),
+            speaker: String::new(),
+            text: String::new(),
+            choices: Vec::new(),
+            log: Vec::new(),
+        }
+    }
+    /// Adds a character with the name of its default state and its other states.
+    pub fn add_character(&mut self, name: &str, default: &str, states: &[&str]) {
+        let mut set: HashSet<String> = states.iter().map(|s| s.to_string()).collect();
+        set.insert(default.to_string());
+        self.characters.insert(name.to_string(), (default.to_string(), set));
+    }
+    /// Adds the name of a background.
+    pub fn add_background(&mut self, name: &str) {
+        self.backgrounds.insert(name.to_string());
+    }
+    /// Adds the name of some music.
+    pub fn add_music(&mut self, name: &str) {
+        self.music_library.insert(name.to_string());
+    }
+    /// Adds the name of a sound effect.
+    pub fn add_sfx(&mut self, name: &str) {
+        self.sfx_library.insert(name.to_string());
+    }
+    /// Adds the name of a voice line.
+    pub fn add_voice(&mut self, name: &str) {
+        self.voice_library.insert(name.to_string());
+    }
+    /// Loads a script from a [`&str`].
+    pub fn load_script(&mut self, name: &str, content: &str) -> Result<(), ScriptImportError> {
+        self.story.load_from_str(name.to_string(), content)
+    }
+    /// Starts the story from a script and maybe an anchor.
+    pub fn start(&mut self, script: &str, anchor: Option<&str>) {
+        self.story.set_script(script, anchor.map(|a| a.to_string()));
+        self.next();
+    }
+    /// Continues the story like the player pressing continue.
+    pub fn next(&mut self) {
+        runtime::next_step(self);
+    }
+    /// Picks one of the options of the choice the player is making.
+    /// Returns ``false`` if there isn't an option at that index.
+    pub fn choose(&mut self, index: usize) -> bool {
+        runtime::select_choice(self, index)
+    }
+    /// Whether the player has to make a choice before the story can continue.
+    pub fn is_choosing(&self) -> bool {
+        self.story.is_choosing()
+    }
+    /// Whether the story has reached the end.
+    pub fn is_ended(&self) -> bool {
+        self.story.is_ended()
+    }
+}
+
+impl Runtime for Headless {
+    fn script(&mut self) -> &mut Script {
+        &mut self.story
+    }
+    fn show_dialogue(&mut self, speaker: Option<String>, text: String) {
+        if let Some(speaker) = speaker {
+            self.speaker = speaker;
+        }
+        self.text = text.clone();
+        self.log.push((self.speaker.clone(), text));
+    }
+    fn show_choices(&mut self, options: Vec<String>) {
+        self.choices = options;
+    }
+    fn spawn(&mut self, character: &str, entity: &str, pos: Option<(f64, f64)>, transition: Option<&str>) {
+        if let Some(&(ref default, _)) = self.characters.get(character) {
+            self.stage.insert(entity.to_string(), HeadlessEntity {
+                character: character.to_string(),
+                state: default.to_string(),
+                pos,
+                visible: true,
+                transition: transition.map(|t| t.to_string()),
+            });
+        }
+    }
+    fn kill(&mut self, entity: &str, _transition: Option<&str>) {
+        // Transitions are treated as if they have already finished.
+        self.stage.remove(entity);
+    }
+    fn show(&mut self, entity: &str, state: Option<&str>, transition: Option<&str>) {
+        if let Some(e) = self.stage.get_mut(entity) {
+            e.visible = true;
+            if let Some(state) = state {
+                let known = self.characters.get(&e.character)
+                    .map(|&(_, ref states)| states.contains(state))
+                    .unwrap_or(false);
+                if known {
+                    e.state = state.to_string();
+                }
+            }
+            if let Some(t) = transition {
+                e.transition = Some(t.to_string());
+            }
+        }
+    }
+    fn hide(&mut self, entity: &str, transition: Option<&str>) {
+        if let Some(e) = self.stage.get_mut(entity) {
+            e.visible = false;
+            if let Some(t) = transition {
+                e.transition = Some(t.to_string());
+            }
+        }
+    }
+    fn move_entity(&mut self, entity: &str, pos: (f64, f64)) {
+        if let Some(e) = self.stage.get_mut(entity) {
+            e.pos = Some(pos);
+        }
+    }
+    fn set_background(&mut self, name: &str) {
+        if self.backgrounds.contains(name) {
+            self.background = Some(name.to_string());
+        }
+    }
+    fn play_music(&mut self, name: &str, _fade: Option<f64>) {
+        if self.music_library.contains(name) {
+            self.music = Some(name.to_string());
+        }
+    }
+    fn stop_music(&mut self, _fade: Option<f64>) {
+        self.music = None;
+    }
+    fn set_volume(&mut self, channel: Channel, volume: f32) {
+        self.volume.set(channel, volume);
+    }
+    fn play_sfx(&mut self, name: &str) {
+        if self.sfx_library.contains(name) {
+            self.sounds.push(name.to_string());
+        }
+    }
+    fn play_voice(&mut self, name: &str) {
+        if self.voice_library.contains(name) {
+            self.voice = Some(name.to_string());
+        }
+    }
+    fn stop_voice(&mut self) {
+        self.voice = None;
+    }
+}
+
+#[test]
+fn test_headless() {
+    let mut story = Headless::new();
+    story.add_character("Aiko", "happy", &["sad"]);
+    story.add_background("park");
+    story.add_music("theme");
+    story.add_sfx("door");
+    story.add_voice("aiko_001");
+    story.load_script("main", r#"
+    :start
+        STAGE 'park'
+        PLAY 'theme'
+        SFX 'door'
+        SFX 'creak'
+        VOLUME music 0.5
+        SPAWN 'Aiko' at (1.0, 2.0)
+        SET player = "Hiro"
+        "Aiko": "Hi [player]!" voice 'aiko_001'
+        CHOICE
+            "Stay" -> :stay
+            "Leave" -> :leave
+        ENDCHOICE
+    :stay
+        MOVE 'Aiko' (3.0, 2.0)
+        "Aiko": "Yay!"
+        END
+    :leave
+        KILL 'Aiko'
+        STOP fade 1.0
+        "Bye..."
+        END
+    "#).unwrap();
+    story.start("main", None);
+    assert_eq!(story.background, Some("park".to_string()));
+    assert_eq!(story.music, Some("theme".to_string()));
+    assert_eq!(story.sounds, vec!["door".to_string()]);
+    assert_eq!(story.volume.music, 0.5);
+    assert_eq!((story.speaker.as_str(), story.text.as_str()), ("Aiko", "Hi Hiro!"));
+    assert_eq!(story.stage["Aiko"].pos, Some((1., 2.)));
+    assert_eq!(story.voice, Some("aiko_001".to_string()));
+    story.next();
+    assert_eq!(story.voice, None);
+    assert!(story.is_choosing());
+    assert_eq!(story.choices, vec!["Stay".to_string(), "Leave".to_string()]);
+    assert!(story.choose(1));
+    assert!(story.stage.is_empty());
+    assert_eq!(story.music, None);
+    assert_eq!(story.text, "Bye...");
+    story.next();
+    assert!(story.is_ended());
+    assert_eq!(story.log.len(), 2);
+}
+
+#[test]
+fn test_headless_voiced_continue() {
+    use super::validate::{Names, Problem};
+    let mut story = Headless::new();
+    story.add_voice("aiko_001");
+    story.add_voice("aiko_002");
+    story.load_script("main", r#"
+    :start
+        "Aiko": "Hello." voice 'aiko_001'
+        "How are you?" voice 'aiko_002'
+        "Anyway."
+        "Bye." voice 'aiko_003'
+    "#).unwrap();
+    story.start("main", None);
+    assert_eq!(story.voice, Some("aiko_001".to_string()));
+    story.next();
+    assert_eq!((story.speaker.as_str(), story.text.as_str()), ("Aiko", "How are you?"));
+    assert_eq!(story.voice, Some("aiko_002".to_string()));
+    story.next();
+    assert_eq!(story.voice, None);
+    let mut names = Names::new();
+    names.voice.insert("aiko_001".to_string());
+    names.voice.insert("aiko_002".to_string());
+    let problems: Vec<Problem> = story.story.validate(&names).into_iter().map(|d| d.problem).collect();
+    assert_eq!(problems, vec![Problem::UnknownVoice("aiko_003".to_string())]);
+}
+#[test]
+fn test_headless_condition() {
+    let script = r#"
+    :start
+        CHOICE
+            "Be nice" -> :nice
+            "Be rude" -> :rude
+        ENDCHOICE
+    :nice
+        SET affection = affection + 3
+        -> :end
+    :rude
+        SET affection = affection - 1
+    :end
+        IF affection >= 3
+            "Aiko": "I like you!"
+        ELSE
+            "Aiko": "Hmph."
+        ENDIF
+        "Aiko": "See you tomorrow."
+        END
+    "#;
+    for &(choice, reply) in &[(0, "I like you!"), (1, "Hmph.")] {
+        let mut story = Headless::new();
+        story.load_script("main", script).unwrap();
+        story.start("main", None);
+        assert!(story.choose(choice));
+        assert_eq!(story.text, reply);
+        story.next();
+        assert_eq!(story.text, "See you tomorrow.");
+        story.next();
+        assert!(story.is_ended());
+    }
+}
+#[test]
+fn test_headless_call() {
+    let mut story = Headless::new();
+    story.load_script("main", r#"
+    :start
+        "Good morning."
+        CALL routines:morning_routine
+        "Off to school."
+        RETURN
+    "#).unwrap();
+    story.load_script("routines", r#"
+    :morning_routine
+        "Brush teeth."
+        CALL routines:breakfast
+        "Get dressed."
+        RETURN
+    :breakfast
+        "Eat toast."
+        RETURN
+    "#).unwrap();
+    story.start("main", None);
+    assert_eq!(story.text, "Good morning.");
+    story.next();
+    assert_eq!(story.text, "Brush teeth.");
+    story.next();
+    assert_eq!(story.text, "Eat toast.");
+    story.next();
+    assert_eq!(story.text, "Get dressed.");
+    story.next();
+    assert_eq!(story.text, "Off to school.");
+    assert!(!story.is_ended());
+    story.next();
+    // Nothing called the main script so the last RETURN ends the story.
+    assert!(story.is_ended());
+    story.next();
+    assert!(story.is_ended());
+    assert_eq!(story.log.len(), 5);
+}
+#[test]
+fn test_headless_falls_off_the_end() {
+    let mut story = Headless::new();
+    story.load_script("main", r#"
+    :start
+        "The last line."
+    "#).unwrap();
+    story.start("main", None);
+    story.next();
+    assert!(story.is_ended());
+}
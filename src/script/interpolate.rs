@@ -0,0 +1,57 @@
+//! Puts the values of variables into text that is shown to the player.
+//!
+//! A variable is written in dialogue or a speaker's name using square brackets like ``[coins]``.
+//! Use ``[[`` to write a ``[``.
+//! If the variable hasn't been set, the text is left as it was written (e.g. ``[coins]``)
+//! so that it is easy to spot in the game.
+
+use std::collections::HashMap;
+use super::expression::Value;
+
+/// Replaces each ``[name]`` in the text with the value of the variable.
+/// Numbers are turned into text using ``number_format``.
+pub fn interpolate(text: &str, variables: &HashMap<String, Value>, number_format: &dyn Fn(f64) -> String) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('[') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if rest.starts_with("[[") {
+            result.push('[');
+            rest = &rest[2..];
+            continue
+        }
+        let value = rest.find(']')
+            .map(|end| (&rest[1..end], end))
+            .and_then(|(name, end)| variables.get(name.trim()).map(|value| (value, end)));
+        match value {
+            Some((value, end)) => {
+                match *value {
+                    Value::Number(n) => result.push_str(&number_format(n)),
+                    ref value => result.push_str(&value.to_string()),
+                }
+                rest = &rest[end + 1..];
+            },
+            None => {
+                result.push('[');
+                rest = &rest[1..];
+            },
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// The default way numbers are shown which is the same as how [`Value`] is displayed.
+pub fn default_number_format(number: f64) -> String {
+    Value::Number(number).to_string()
+}
+
+#[test]
+fn test_interpolate() {
+    let mut variables = HashMap::new();
+    variables.insert("player".to_string(), Value::Text("Aiko".to_string()));
+    variables.insert("coins".to_string(), Value::Number(3.));
+    assert_eq!(interpolate("[player] has [coins] coins", &variables, &default_number_format), "Aiko has 3 coins");
+    assert_eq!(interpolate("[coins] [[coins] [unknown]", &variables, &|n| format!("{:.2}", n)), "3.00 [coins] [unknown]");
+}
@@ -4,33 +4,59 @@
 //! * Dialogue to change
 //! * Characters to be spawned, killed, shown, and hidden
 //! * The background to change
+//! * Music and sound effects to be played
+//! * Variables to be set and checked, and shown in dialogue using ``[name]``
 //!
 //! See the example_vn repo's script.txt file for an example of what can be done
 //! with this language.
 
 pub mod parser;
+pub mod runtime;
+pub mod headless;
+pub mod validate;
+pub mod graph;
+pub mod bundle;
+mod expression;
+mod interpolate;
 
 use std::{
     path::Path,
     fs::File,
     io::Read,
+    collections::HashMap,
 };
 use indexmap::IndexMap;
 
 use super::{
     game::Game,
     error::ScriptImportError,
+    music::volume::Channel,
+};
+use self::{
+    parser::translate_with_source,
+    runtime::Runtime,
+    interpolate::{
+        interpolate,
+        default_number_format,
+    },
+};
+pub use self::expression::{
+    Value,
+    Expression,
+    Operator,
 };
-use self::parser::translate;
 
 /// Represents an action dictated by the script
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ScriptStep {
     /// A dialogue that consists of the name of the speaker and the text.
-    /// (Name, Text)
-    Dialogue(String, String),
+    /// The voice line is played while the dialogue is on screen.
+    /// (Name, Text, Some(Voice))
+    Dialogue(String, String, Option<String>),
     /// Only changes the text in the textbox. Doesn't change the speaker.
-    DialogueContinue(String),
+    /// The voice line is played while the dialogue is on screen.
+    /// (Text, Some(Voice))
+    DialogueContinue(String, Option<String>),
     /// Shows an entity on the screen and if the state is not ``None`` will change that
     /// entity's state to the state named.
     /// (Entity, Some(State), Some(Transition)
@@ -52,31 +78,115 @@ pub enum ScriptStep {
     /// Go to a specific script or part of a script
     /// (ScriptName, AnchorName)
     GoTo(Option<String>, Option<String>),
-    /// Play music
-    Play(String),
+    /// Go to a specific script or part of a script and come back here when a [`ScriptStep::Return`]
+    /// is reached.
+    /// (ScriptName, AnchorName)
+    Call(Option<String>, Option<String>),
+    /// Go back to the step after the last [`ScriptStep::Call`].
+    /// If there isn't anywhere to go back to then this acts like [`ScriptStep::End`].
+    Return,
+    /// Let the player pick one of the options which goes to a specific script or part of a script.
+    /// Vec<(Text, ScriptName, AnchorName)>
+    Choice(Vec<(String, Option<String>, Option<String>)>),
+    /// Set a variable to the value of an expression.
+    /// (VariableName, Expression)
+    Set(String, Expression),
+    /// If the condition is false then the number of steps given are skipped.
+    /// (Condition, StepsToSkip)
+    If(Expression, usize),
+    /// The start of the ELSE part of an IF. It is only reached after the IF part has finished
+    /// so the number of steps given (the ELSE part) are skipped.
+    Else(usize),
+    /// Play music, crossfading from the music that is playing over a number of seconds.
+    /// (Music, Some(Fade))
+    Play(String, Option<f64>),
+    /// Stop the music, fading it out over a number of seconds.
+    Stop(Option<f64>),
+    /// Play a sound effect once.
+    Sfx(String),
+    /// Sets the volume of a channel of audio from 0 to 1.
+    Volume(Channel, f32),
+    /// Wait for a number of seconds before continuing.
+    /// The player can skip the wait by continuing.
+    Wait(f64),
+    /// Sets how many seconds dialogue stays on screen before the story continues on its own.
+    /// ``None`` turns it off so the player has to continue the story.
+    Auto(Option<f64>),
     /// End of the script/game
     End,
 }
 
+/// Where the steps of a script came from so that problems with them can point at the script file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Source {
+    /// The file that the script was read from.
+    /// ``None`` if the script wasn't read from a file.
+    pub file: Option<String>,
+    /// The line that each step of each anchor is on. (Starts from 1)
+    pub lines: IndexMap<String, Vec<usize>>,
+}
+impl Source {
+    /// The line that a step of an anchor is on.
+    pub fn line(&self, anchor: &str, step: usize) -> Option<usize> {
+        self.lines.get(anchor).and_then(|lines| lines.get(step)).cloned()
+    }
+}
+
 /// The struct in charge of the story in the VN and also in charge of parsing everything.
 pub struct Script {
     /// The current position in the script.
     step: usize,
+    /// The position of the step that the story stopped at last, like the dialogue that is on screen.
+    current: usize,
     /// The current script that is being used.
     pub script: Vec<ScriptStep>,
     /// A collection of all the scripts that can be used.
     pub scripts: IndexMap<String, IndexMap<String, Vec<ScriptStep>>>,
+    /// Where each script came from.
+    /// Scripts that weren't parsed from text, like the ones in a bundle, don't have one.
+    pub sources: HashMap<String, Source>,
+    /// The script that the story starts from, set by the Script config file or a bundle.
+    pub default: Option<String>,
     /// The current index of what script is being used and what anchor is being used.
     pub index: (usize, usize),
+    /// Where each option of the choice the player is currently making goes to.
+    /// (ScriptName, AnchorName)
+    /// This is empty if the player isn't making a choice.
+    pub choices: Vec<(Option<String>, Option<String>)>,
+    /// The variables that have been set by the story.
+    pub variables: HashMap<String, Value>,
+    /// Where to go back to when a RETURN is reached. The last item is where the latest CALL came from.
+    /// (ScriptIndex, AnchorIndex, Step)
+    pub calls: Vec<(usize, usize, usize)>,
+    /// The speaker of the last dialogue before any variables are put in.
+    pub speaker: String,
+    /// How many seconds are left before the story continues on its own.
+    /// This is set by [`ScriptStep::Wait`] and by dialogue when [`Script::auto_advance`] is set.
+    pub timer: Option<f64>,
+    /// How many seconds dialogue stays on screen before the story continues on its own.
+    /// This is set by [`ScriptStep::Auto`].
+    pub auto_advance: Option<f64>,
+    /// Turns numbers into text when they are put into dialogue.
+    number_format: Box<dyn Fn(f64) -> String>,
 }
 impl Script {
     /// Create a new [`Script`] struct.
     pub fn new() -> Script {
         Script {
             step: 0,
+            current: 0,
             script: Vec::new(),
             scripts: IndexMap::new(),
-            index: (0, 0)
+            sources: HashMap::new(),
+            default: None,
+            index: (0, 0),
+            choices: Vec::new(),
+            variables: HashMap::new(),
+            calls: Vec::new(),
+            speaker: String::new(),
+            timer: None,
+            auto_advance: None,
+            number_format: Box::new(default_number_format),
         }
     }
     /// Set a script with its name and maybe its anchor.
@@ -110,20 +220,94 @@ impl Script {
         self.step = 0;
         self.index = index;
     }
+    /// Whether the player has to make a choice before the story can continue.
+    pub fn is_choosing(&self) -> bool {
+        !self.choices.is_empty()
+    }
+    /// Set a variable which can be used by the conditions in the script.
+    pub fn set_variable<S: ToString>(&mut self, name: S, value: Value) {
+        self.variables.insert(name.to_string(), value);
+    }
+    /// Get the value of a variable if it has been set.
+    pub fn get_variable(&self, name: &str) -> Option<&Value> {
+        self.variables.get(name)
+    }
+    /// Set how numbers are shown when a variable is put into dialogue using ``[name]``.
+    ///
+    /// By default whole numbers are shown without a decimal point.
+    pub fn set_number_format<F: Fn(f64) -> String + 'static>(&mut self, format: F) {
+        self.number_format = Box::new(format);
+    }
+    /// Replaces each ``[name]`` in the text with the value of the variable.
+    /// A variable that hasn't been set is left as ``[name]`` and ``[[`` is a ``[``.
+    pub fn interpolate(&self, text: &str) -> String {
+        interpolate(text, &self.variables, &*self.number_format)
+    }
+    /// The position of the step that the story stopped at last, like the dialogue that is on screen.
+    /// Executing this step again shows the same thing to the player.
+    pub fn current_step(&self) -> usize {
+        self.current
+    }
+    /// Whether the story has reached the end.
+    pub fn is_ended(&self) -> bool {
+        match self.script.get(self.current) {
+            Some(&ScriptStep::End) => true,
+            // A RETURN that doesn't have anywhere to go back to acts like an END.
+            Some(&ScriptStep::Return) => self.calls.is_empty(),
+            _ => false,
+        }
+    }
+    /// The names of the script and the anchor at an index.
+    pub fn names(&self, index: (usize, usize)) -> Option<(&str, &str)> {
+        let (script, map) = self.scripts.get_index(index.0)?;
+        let (anchor, _) = map.get_index(index.1)?;
+        Some((script, anchor))
+    }
+    /// The index of a script and an anchor using their names.
+    pub fn find(&self, script: &str, anchor: &str) -> Option<(usize, usize)> {
+        let (i, _, map) = self.scripts.get_full(script)?;
+        let (j, _, _) = map.get_full(anchor)?;
+        Some((i, j))
+    }
+    /// Go to a specific step using the index of the script and the index of the anchor.
+    /// If the script or anchor doesn't exist then the script will end.
+    pub fn set_position(&mut self, index: (usize, usize), step: usize) {
+        self.script = match self.scripts.get_index(index.0) {
+            Some((_, map)) => match map.get_index(index.1) {
+                Some((_, v)) => v.clone(),
+                None => vec![ScriptStep::End],
+            },
+            None => vec![ScriptStep::End],
+        };
+        self.index = index;
+        self.step = step;
+    }
     /// Go to the next script.
+    /// If there isn't a next script then the story ends.
     pub fn next_script(&mut self) {
-        if let Some((_, map)) = self.scripts.get_index(self.index.0) {
-            self.index.1 += 1;
-            if let Some((_, v)) = map.get_index(self.index.1) {
-                self.script = v.clone();
-                self.step = 0;
-            } else {
-                self.script = vec![ScriptStep::End];
-            }
-        }
+        self.index.1 += 1;
+        let next = self.scripts.get_index(self.index.0)
+            .and_then(|(_, map)| map.get_index(self.index.1))
+            .map(|(_, v)| v.clone());
+        self.script = next.unwrap_or_else(|| vec![ScriptStep::End]);
+        self.step = 0;
+    }
+    /// Goes to a specific script or part of a script.
+    /// If the name of the script is ``None`` then the current script is used.
+    pub fn go_to(&mut self, name: Option<String>, anchor: Option<String>) {
+        let name = match name {
+            Some(n) => n,
+            None => match self.scripts.get_index(self.index.0) {
+                Some((k, _)) => k.to_string(),
+                None => String::new(),
+            },
+        };
+        self.set_script(&name, anchor);
     }
     /// Load a script into the collection of scripts.
     pub fn load_script(&mut self, name: String, scripts: IndexMap<String, Vec<ScriptStep>>) {
+        // The lines of the old script don't match the new one.
+        self.sources.remove(&name);
         self.scripts.insert(name, scripts);
     }
     /// Load a number of scripts into the collection of scripts.
@@ -134,147 +318,147 @@ impl Script {
     }
     /// Load scripts from a [`&str`].
     pub fn load_from_str(&mut self, name: String, content: &str) -> Result<(), ScriptImportError> {
-        self.load_script(name, translate(content)?);
+        let (map, source) = translate_with_source(content)?;
+        self.load_script(name.clone(), map);
+        self.sources.insert(name, source);
         Ok(())
     }
     /// Load scripts from an external file.
     pub fn load_from_file<P: AsRef<Path>>(&mut self, name: String, path: P) -> Result<(), ScriptImportError> {
         let mut buffer = String::new();
-        File::open(path)?.read_to_string(&mut buffer)?;
-        self.load_from_str(name,&buffer)
+        File::open(&path)?.read_to_string(&mut buffer)?;
+        self.load_from_str(name.clone(), &buffer)
+            .map_err(|e| e.with_file(path.as_ref().display()))?;
+        if let Some(source) = self.sources.get_mut(&name) {
+            source.file = Some(path.as_ref().display().to_string());
+        }
+        Ok(())
     }
 }
 
 impl Game {
     /// Load the next step of the script and execute the step.
     pub fn next_step(&mut self) {
-        let length = self.story.script.len();
-        let mut execute = true;
-        while execute {
-            if self.story.step < length {
-                self.story.step += 1;
-                let possible_step;
-                {
-                    possible_step = self.story.script.get(self.story.step - 1).cloned()
-                }
-                if let Some(step) = possible_step {
-                    execute = self.execute_step(step);
-                } else {
-                    self.story.next_script();
-                    self.next_step();
-                    break
-                }
-            } else {
-                self.story.next_script();
-                self.next_step();
-                break
-            }
-        }
+        runtime::next_step(self);
+    }
+    /// Picks one of the options of the choice the player is currently making
+    /// and continues the story from where that option goes to.
+    /// Does nothing if there isn't an option at that index.
+    pub fn select_choice(&mut self, index: usize) {
+        runtime::select_choice(self, index);
+    }
+}
+
+impl Runtime for Game {
+    fn script(&mut self) -> &mut Script {
+        &mut self.story
     }
-    fn execute_step(&mut self, step: ScriptStep) -> bool {
-        // boolean value is whether to continue or not.
-        // True => Continue
-        // False => Stop
-        use self::ScriptStep::*;
-        match step {
-            Dialogue(speaker, content) => {
-                self.ui.textbox.set_text(content.clone());
+    fn show_dialogue(&mut self, speaker: Option<String>, text: String) {
+        self.ui.textbox.set_text(text.clone());
+        let speaker = match speaker {
+            Some(speaker) => {
                 if let Some(ref mut e) = self.ui.speaker_box {
-                    e.set_text(speaker.to_string());
-                }
-                false
-            },
-            DialogueContinue(content) => {
-                self.ui.textbox.set_text(content.clone());
-                false
-            },
-            Show(image, possible_state, trans) => {
-                if let Some(e) = self.stage.get_mut(&image) {
-                    e.set_visible(true);
-                }
-                if let Some(state) = possible_state {
-                    self.change_entity_state(&image, &state);
-                }
-                if let Some(t) = trans {
-                    self.apply_character_transition(&image, &t);
-                }
-                true
-            },
-            Hide(image, trans) => {
-                let mut vis = false;
-                if let Some(t) = trans {
-                    self.apply_character_transition(&image, &t);
-                    if let Some(e) = self.stage.get_mut(&image) {
-                        e.to_be_hidden = true;
-                    }
-                    vis = true;
-                }
-                if let Some(e) = self.stage.get_mut(&image) {
-                    e.visible = vis;
-                }
-                true
-            },
-            Spawn(character, entity, maybe_pos, maybe_trans) => {
-                let name = entity.clone().unwrap_or(character.clone());
-                self.add_to_stage(name.clone(), character.clone());
-                if let Some(pos) = maybe_pos {
-                    self.move_character(&name, pos.into());
-                }
-                if let Some(trans) = maybe_trans {
-                    self.apply_character_transition(&name, &trans);
+                    e.set_text(speaker.clone());
                 }
-                true
-            },
-            Kill(name, trans) => {
-                let mut rem = true;
-                if let Some(t) = trans {
-                    self.apply_character_transition(&name, &t);
-                    if let Some(e) = self.stage.get_mut(&name) {
-                        e.to_be_killed = true;
-                    }
-                    rem = false;
-                }
-                if rem {
-                    self.stage.remove(&name);
-                }
-                true
-            },
-            Move(name, pos) => {
-                self.move_character(&name, pos.into());
-                true
-            },
-            Stage(bg) => {
-                self.set_background(&bg);
-                true
+                speaker
             },
-            GoTo(name, anchor) => {
-                let name = {
-                    if let Some(n) = name {
-                        n
-                    } else {
-                        if let Some((k, _)) = self.story.scripts.get_index(self.story.index.0) {
-                            k.to_string()
-                        } else {
-                            String::new()
-                        }
-                    }
-                };
-                self.story.set_script(&name, anchor);
-                true
-            },
-            Play(name) => {
-                if let Some(ref mut m) = self.music {
-                    // Ignore the result.
-                    // In the future, this might actually be used.
-                    // Maybe...
-                    let _ = m.set_music(&name);
-                }
-                true
+            // The speaker is the same as the last dialogue.
+            None => self.story.interpolate(&self.story.speaker),
+        };
+        self.ui.backlog.push(speaker, text);
+    }
+    fn dialogue_shown(&mut self) {
+        self.mark_read();
+        self.record_history();
+    }
+    fn show_choices(&mut self, options: Vec<String>) {
+        self.ui.choices.set_options(options);
+    }
+    fn spawn(&mut self, character: &str, entity: &str, pos: Option<(f64, f64)>, transition: Option<&str>) {
+        let name = entity.to_string();
+        self.add_to_stage(name.clone(), character.to_string());
+        if let Some(pos) = pos {
+            self.move_character(&name, pos.into());
+        }
+        if let Some(trans) = transition {
+            self.apply_character_transition(&name, &trans.to_string());
+        }
+    }
+    fn kill(&mut self, entity: &str, transition: Option<&str>) {
+        let name = entity.to_string();
+        if let Some(t) = transition {
+            self.apply_character_transition(&name, &t.to_string());
+            if let Some(e) = self.stage.get_mut(&name) {
+                e.to_be_killed = true;
             }
-            End => {
-                self.story.step -= 1;
-                false
+        } else {
+            self.stage.shift_remove(&name);
+        }
+    }
+    fn show(&mut self, entity: &str, state: Option<&str>, transition: Option<&str>) {
+        let name = entity.to_string();
+        if let Some(e) = self.stage.get_mut(&name) {
+            e.set_visible(true);
+        }
+        if let Some(state) = state {
+            self.change_entity_state(&name, &state.to_string());
+        }
+        if let Some(t) = transition {
+            self.apply_character_transition(&name, &t.to_string());
+        }
+    }
+    fn hide(&mut self, entity: &str, transition: Option<&str>) {
+        let name = entity.to_string();
+        let mut vis = false;
+        if let Some(t) = transition {
+            self.apply_character_transition(&name, &t.to_string());
+            if let Some(e) = self.stage.get_mut(&name) {
+                e.to_be_hidden = true;
             }
+            vis = true;
+        }
+        if let Some(e) = self.stage.get_mut(&name) {
+            e.visible = vis;
+        }
+    }
+    fn move_entity(&mut self, entity: &str, pos: (f64, f64)) {
+        self.move_character(&entity.to_string(), pos.into());
+    }
+    fn set_background(&mut self, name: &str) {
+        Game::set_background(self, &name.to_string());
+    }
+    fn play_music(&mut self, name: &str, fade: Option<f64>) {
+        if let Some(ref mut m) = self.music {
+            // Ignore the result.
+            // In the future, this might actually be used.
+            // Maybe...
+            let _ = m.fade_to(name, fade.unwrap_or(0.));
         }
     }
-}
\ No newline at end of file
+    fn stop_music(&mut self, fade: Option<f64>) {
+        if let Some(ref mut m) = self.music {
+            m.stop(fade.unwrap_or(0.));
+        }
+    }
+    fn play_sfx(&mut self, name: &str) {
+        if let Some(ref mut m) = self.music {
+            let _ = m.sfx.play(name);
+        }
+    }
+    fn set_volume(&mut self, channel: Channel, volume: f32) {
+        // Only the volume that is playing changes. The player's settings are kept.
+        if let Some(ref mut m) = self.music {
+            m.set_volume(channel, volume);
+        }
+    }
+    fn play_voice(&mut self, name: &str) {
+        if let Some(ref mut m) = self.music {
+            let _ = m.voice.play(name);
+        }
+    }
+    fn stop_voice(&mut self) {
+        if let Some(ref mut m) = self.music {
+            m.voice.stop();
+        }
+    }
+}
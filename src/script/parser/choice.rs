@@ -0,0 +1,46 @@
+use nom::types::CompleteStr;
+use super::{
+    super::ScriptStep,
+    values::speech,
+    goto::target,
+};
+
+named!(pub choice(CompleteStr) -> ScriptStep,
+    map!(
+        preceded!(
+            tag!("CHOICE"),
+            // Once a CHOICE is found, an error inside of it shouldn't be backtracked from.
+            return_error!(
+                terminated!(
+                    many1!(
+                        ws_comments!(
+                            pair!(
+                                speech,
+                                target
+                            )
+                        )
+                    ),
+                    ws_comments!(tag!("ENDCHOICE"))
+                )
+            )
+        ),
+        |list| {
+            let options = list.into_iter()
+                .map(|(text, (name, anchor))| (text, name, anchor))
+                .collect();
+            ScriptStep::Choice(options)
+        }
+    )
+);
+
+#[test]
+fn test_parser_choice() {
+    let some = |s: &str| Some(s.to_string());
+    assert_eq!(choice(CompleteStr(r#"CHOICE
+        "Go to the forest" -> forest:entrance
+        "Stay at home" -> :home
+    ENDCHOICE"#)).unwrap().1, ScriptStep::Choice(vec![
+        ("Go to the forest".to_string(), some("forest"), some("entrance")),
+        ("Stay at home".to_string(), None, some("home")),
+    ]));
+}
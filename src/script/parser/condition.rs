@@ -0,0 +1,127 @@
+use nom::types::CompleteStr;
+use super::{
+    super::ScriptStep,
+    expression::{expression, identifier},
+    translate_to_step,
+    remaining,
+};
+
+named!(pub set(CompleteStr) -> ScriptStep,
+    map!(
+        preceded!(
+            tag!("SET"),
+            ws!(
+                separated_pair!(
+                    identifier,
+                    tag!("="),
+                    expression
+                )
+            )
+        ),
+        |(name, value)| ScriptStep::Set(name.to_string(), value)
+    )
+);
+
+named!(otherwise(CompleteStr) -> (usize, Vec<(usize, ScriptStep)>),
+    do_parse!(
+        at: remaining >>
+        tag!("ELSE") >>
+        steps: translate_to_step >>
+        ((at, steps))
+    )
+);
+
+named!(pub conditional(CompleteStr) -> Vec<(usize, ScriptStep)>,
+    do_parse!(
+        at: remaining >>
+        tag!("IF") >>
+        // Once an IF is found, an error inside of it shouldn't be backtracked from
+        // so that the error points to where it actually is.
+        parts: return_error!(
+            do_parse!(
+                condition: ws!(expression) >>
+                then: translate_to_step >>
+                otherwise: opt!(
+                    ws_comments!(
+                        otherwise
+                    )
+                ) >>
+                ws_comments!(tag!("ENDIF")) >>
+                ((condition, then, otherwise))
+            )
+        ) >>
+        ({
+            let (condition, then, otherwise) = parts;
+            // The steps are kept flat so an IF is turned into steps that skip over the part
+            // that isn't used.
+            let mut steps = Vec::new();
+            match otherwise {
+                Some((else_at, otherwise)) => {
+                    steps.push((at, ScriptStep::If(condition, then.len() + 1)));
+                    steps.extend(then);
+                    steps.push((else_at, ScriptStep::Else(otherwise.len())));
+                    steps.extend(otherwise);
+                },
+                None => {
+                    steps.push((at, ScriptStep::If(condition, then.len())));
+                    steps.extend(then);
+                },
+            }
+            steps
+        })
+    )
+);
+
+#[test]
+fn test_parser_condition() {
+    use super::super::expression::{Expression, Operator, Value};
+    let steps = |s: &str| conditional(CompleteStr(s)).unwrap().1.into_iter().map(|(_, s)| s).collect::<Vec<_>>();
+    let line = |t: &str| ScriptStep::Dialogue("Aiko".to_string(), t.to_string(), None);
+    let at_least = |name: &str, n: f64| Expression::Binary(
+        Box::new(Expression::Variable(name.to_string())),
+        Operator::GreaterEqual,
+        Box::new(Expression::Value(Value::Number(n))),
+    );
+    assert_eq!(set(CompleteStr("SET affection = affection + 1")).unwrap().1, ScriptStep::Set(
+        "affection".to_string(),
+        Expression::Binary(
+            Box::new(Expression::Variable("affection".to_string())),
+            Operator::Add,
+            Box::new(Expression::Value(Value::Number(1.))),
+        ),
+    ));
+    assert_eq!(steps(r#"IF affection >= 3
+        "Aiko": "I like you!"
+    ELSE
+        "Aiko": "Hmph."
+    ENDIF"#), vec![
+        ScriptStep::If(at_least("affection", 3.), 2),
+        line("I like you!"),
+        ScriptStep::Else(1),
+        line("Hmph."),
+    ]);
+    assert_eq!(steps(r#"IF affection >= 3
+        "Aiko": "I like you!"
+    ENDIF"#), vec![
+        ScriptStep::If(at_least("affection", 3.), 1),
+        line("I like you!"),
+    ]);
+    // The skip counts of an outer IF include every step of the IFs inside of it.
+    assert_eq!(steps(r#"IF affection >= 3
+        IF trust >= 2
+            "Aiko": "I trust you."
+        ELSE
+            "Aiko": "Not yet."
+        ENDIF
+    ELSE
+        "Aiko": "Hmph."
+    ENDIF"#), vec![
+        ScriptStep::If(at_least("affection", 3.), 5),
+        ScriptStep::If(at_least("trust", 2.), 2),
+        line("I trust you."),
+        ScriptStep::Else(1),
+        line("Not yet."),
+        ScriptStep::Else(1),
+        line("Hmph."),
+    ]);
+}
@@ -1,13 +1,31 @@
 use nom::types::CompleteStr;
 use super::{
     super::ScriptStep,
-    values::speech,
+    values::{
+        speech,
+        quote,
+    },
 };
 
+// The voice line that is played with the dialogue, like ``voice 'aiko_001'``.
+named!(voice(CompleteStr) -> Option<String>,
+    opt!(
+        ws!(
+            preceded!(
+                tag!("voice"),
+                quote
+            )
+        )
+    )
+);
+
 named!(pub dialogue_continue(CompleteStr) -> ScriptStep,
     map!(
-        speech,
-        |text|{ScriptStep::DialogueContinue(text.to_string())}
+        pair!(
+            speech,
+            voice
+        ),
+        |(text, voice)| ScriptStep::DialogueContinue(text, voice)
     )
 );
 
@@ -19,21 +37,36 @@ named!(pub dialogue(CompleteStr) -> ScriptStep,
                 preceded!(
                     tag!(":"),
                     ws!(
-                        delimited!(
-                            tag!("\""),
-                            take_until!("\""),
-                            tag!("\"")
+                        pair!(
+                            speech,
+                            voice
                         )
                     )
                 )
             )
         ),
-        |(speaker, content)| ScriptStep::Dialogue(speaker.to_string(), content.to_string())
+        |(speaker, (content, voice))| ScriptStep::Dialogue(speaker, content, voice)
     )
 );
 
 #[test]
 fn test_dialogue() {
     println!("{:?}", dialogue_continue(CompleteStr("\"hi there\"")));
-    println!("{:?}", dialogue(CompleteStr(r#""Main Character" : "Hi there""#)))
+    println!("{:?}", dialogue(CompleteStr(r#""Main Character" : "Hi there""#)));
+    println!("{:?}", dialogue(CompleteStr(r#""Aiko": """
+        This goes over
+        multiple lines.
+    """"#)));
+    assert_eq!(
+        dialogue(CompleteStr(r#""Aiko": "Hello" voice 'aiko_001'"#)),
+        Ok((CompleteStr(""), ScriptStep::Dialogue("Aiko".to_string(), "Hello".to_string(), Some("aiko_001".to_string()))))
+    );
+    assert_eq!(
+        dialogue_continue(CompleteStr(r#""And goodbye." voice 'aiko_002'"#)),
+        Ok((CompleteStr(""), ScriptStep::DialogueContinue("And goodbye.".to_string(), Some("aiko_002".to_string()))))
+    );
+    assert_eq!(
+        dialogue_continue(CompleteStr("\"No voice.\"")),
+        Ok((CompleteStr(""), ScriptStep::DialogueContinue("No voice.".to_string(), None)))
+    );
 }
\ No newline at end of file
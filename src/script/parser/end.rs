@@ -1,9 +1,16 @@
 use nom::types::CompleteStr;
-use super::super::ScriptStep;
+use super::{
+    super::ScriptStep,
+    expression::identifier,
+};
 
 named!(pub end(CompleteStr) -> ScriptStep,
     map!(
-        tag!("END"),
+        // Makes sure that words like ENDIF aren't read as END.
+        terminated!(
+            tag!("END"),
+            not!(identifier)
+        ),
         |_| ScriptStep::End
     )
 );
\ No newline at end of file
@@ -0,0 +1,192 @@
+use nom::{
+    double,
+    Err,
+    ErrorKind,
+    IResult,
+    types::CompleteStr,
+};
+use super::{
+    super::expression::{
+        Expression,
+        Operator,
+        Value,
+    },
+    values::speech,
+};
+
+named!(pub identifier(CompleteStr) -> CompleteStr,
+    verify!(
+        take_while1!(|c: char| c.is_alphanumeric() || c == '_'),
+        |name: CompleteStr| !name.starts_with(|c: char| c.is_digit(10))
+    )
+);
+
+/// Parses a keyword like ``AND`` that isn't the start of a longer name like ``ANDY``.
+fn keyword<'a>(input: CompleteStr<'a>, word: &str) -> IResult<CompleteStr<'a>, CompleteStr<'a>> {
+    let (rest, word) = tag!(input, word)?;
+    match rest.chars().next() {
+        Some(c) if c.is_alphanumeric() || c == '_' => Err(Err::Error(error_position!(input, ErrorKind::Tag))),
+        _ => Ok((rest, word)),
+    }
+}
+
+named!(pub expression(CompleteStr) -> Expression,
+    call!(or)
+);
+
+named!(or(CompleteStr) -> Expression,
+    do_parse!(
+        first: and >>
+        rest: many0!(
+            ws!(
+                pair!(
+                    value!(Operator::Or, call!(keyword, "OR")),
+                    and
+                )
+            )
+        ) >>
+        (fold(first, rest))
+    )
+);
+
+named!(and(CompleteStr) -> Expression,
+    do_parse!(
+        first: comparison >>
+        rest: many0!(
+            ws!(
+                pair!(
+                    value!(Operator::And, call!(keyword, "AND")),
+                    comparison
+                )
+            )
+        ) >>
+        (fold(first, rest))
+    )
+);
+
+named!(comparison(CompleteStr) -> Expression,
+    do_parse!(
+        first: sum >>
+        rest: opt!(
+            ws!(
+                pair!(
+                    alt_complete!(
+                        value!(Operator::Equal, tag!("=="))
+                        | value!(Operator::NotEqual, tag!("!="))
+                        | value!(Operator::LessEqual, tag!("<="))
+                        | value!(Operator::GreaterEqual, tag!(">="))
+                        | value!(Operator::Less, tag!("<"))
+                        | value!(Operator::Greater, tag!(">"))
+                    ),
+                    sum
+                )
+            )
+        ) >>
+        (fold(first, rest.into_iter().collect()))
+    )
+);
+
+named!(sum(CompleteStr) -> Expression,
+    do_parse!(
+        first: product >>
+        rest: many0!(
+            ws!(
+                pair!(
+                    alt_complete!(
+                        value!(Operator::Add, tag!("+"))
+                        | value!(Operator::Subtract, tag!("-"))
+                    ),
+                    product
+                )
+            )
+        ) >>
+        (fold(first, rest))
+    )
+);
+
+named!(product(CompleteStr) -> Expression,
+    do_parse!(
+        first: unary >>
+        rest: many0!(
+            ws!(
+                pair!(
+                    alt_complete!(
+                        value!(Operator::Multiply, tag!("*"))
+                        | value!(Operator::Divide, tag!("/"))
+                        | value!(Operator::Remainder, tag!("%"))
+                    ),
+                    unary
+                )
+            )
+        ) >>
+        (fold(first, rest))
+    )
+);
+
+named!(unary(CompleteStr) -> Expression,
+    ws!(
+        alt_complete!(
+            map!(
+                preceded!(call!(keyword, "NOT"), unary),
+                |e| Expression::Not(Box::new(e))
+            )
+            | map!(
+                double,
+                |n| Expression::Value(Value::Number(n))
+            )
+            | map!(
+                preceded!(tag!("-"), unary),
+                |e| Expression::Negate(Box::new(e))
+            )
+            | atom
+        )
+    )
+);
+
+named!(atom(CompleteStr) -> Expression,
+    alt_complete!(
+        map!(
+            speech,
+            |text| Expression::Value(Value::Text(text))
+        )
+        | delimited!(
+            tag!("("),
+            ws!(expression),
+            tag!(")")
+        )
+        | map!(
+            identifier,
+            |name| match name.0 {
+                "true" => Expression::Value(Value::Bool(true)),
+                "false" => Expression::Value(Value::Bool(false)),
+                name => Expression::Variable(name.to_string()),
+            }
+        )
+    )
+);
+
+fn fold(first: Expression, rest: Vec<(Operator, Expression)>) -> Expression {
+    rest.into_iter()
+        .fold(first, |left, (op, right)| Expression::Binary(Box::new(left), op, Box::new(right)))
+}
+
+#[test]
+fn test_parser_expression() {
+    println!("{:?}", expression(CompleteStr("affection + 1")));
+    println!("{:?}", expression(CompleteStr("affection >= 3 AND NOT met_aiko")));
+    println!("{:?}", expression(CompleteStr(r#"(name == "Aiko") OR -2 * 3 < 1"#)));
+}
+#[test]
+fn test_parser_keyword_names() {
+    let var = |name: &str| Box::new(Expression::Variable(name.to_string()));
+    let (rest, e) = expression(CompleteStr("NOTICED AND ANDY OR ORDERED")).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(e, Expression::Binary(
+        Box::new(Expression::Binary(var("NOTICED"), Operator::And, var("ANDY"))),
+        Operator::Or,
+        var("ORDERED"),
+    ));
+    assert_eq!(expression(CompleteStr("NOT NOTICED")).unwrap().1, Expression::Not(var("NOTICED")));
+    // A name straight after another one isn't read as a keyword and the rest of the name.
+    assert_eq!(expression(CompleteStr("met ORDERED")).unwrap().0.trim(), "ORDERED");
+}
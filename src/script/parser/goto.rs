@@ -1,23 +1,50 @@
-use nom::{
-    alphanumeric,
-    types::CompleteStr,
-};
+use nom::types::CompleteStr;
 use super::super::ScriptStep;
 
 named!(pub goto(CompleteStr) -> ScriptStep,
+    map!(
+        target,
+        |(name, anchor)| ScriptStep::GoTo(name, anchor)
+    )
+);
+
+named!(pub call(CompleteStr) -> ScriptStep,
     map!(
         preceded!(
-            tag!("->"),
+            tag!("CALL"),
             ws!(
-                separated_pair!(
-                    opt!(
-                        alphanumeric
-                    ),
-                    tag!(":"),
-                    opt!(
-                        alphanumeric
-                    )
-                )
+                location
+            )
+        ),
+        |(name, anchor)| ScriptStep::Call(name, anchor)
+    )
+);
+
+named!(pub ret(CompleteStr) -> ScriptStep,
+    map!(
+        tag!("RETURN"),
+        |_| ScriptStep::Return
+    )
+);
+
+named!(pub target(CompleteStr) -> (Option<String>, Option<String>),
+    preceded!(
+        tag!("->"),
+        ws!(
+            location
+        )
+    )
+);
+
+named!(location(CompleteStr) -> (Option<String>, Option<String>),
+    map!(
+        separated_pair!(
+            opt!(
+                name
+            ),
+            tag!(":"),
+            opt!(
+                name
             )
         ),
         |(name, anchor)| {
@@ -31,14 +58,28 @@ named!(pub goto(CompleteStr) -> ScriptStep,
                     Some(a.to_string())
                 } else { None }
             };
-            ScriptStep::GoTo(name, anchor)
+            (name, anchor)
         }
     )
 );
 
+named!(name(CompleteStr) -> CompleteStr,
+    take_while1!(|c: char| c.is_alphanumeric() || c == '_')
+);
+
 #[test]
 fn test_parser_goto() {
-    println!("{:?}", goto(CompleteStr(r#"-> name:anchor"#)));
-    println!("{:?}", goto(CompleteStr(r#"-> name:"#)));
-    println!("{:?}", goto(CompleteStr(r#"-> :anchor"#)));
-}
\ No newline at end of file
+    let some = |s: &str| Some(s.to_string());
+    assert_eq!(goto(CompleteStr(r#"-> name:anchor"#)).unwrap().1, ScriptStep::GoTo(some("name"), some("anchor")));
+    assert_eq!(goto(CompleteStr(r#"-> name:"#)).unwrap().1, ScriptStep::GoTo(some("name"), None));
+    assert_eq!(goto(CompleteStr(r#"-> :anchor"#)).unwrap().1, ScriptStep::GoTo(None, some("anchor")));
+}
+
+#[test]
+fn test_parser_call() {
+    assert_eq!(
+        call(CompleteStr(r#"CALL routines:morning_routine"#)).unwrap().1,
+        ScriptStep::Call(Some("routines".to_string()), Some("morning_routine".to_string()))
+    );
+    assert_eq!(ret(CompleteStr(r#"RETURN"#)).unwrap().1, ScriptStep::Return);
+}
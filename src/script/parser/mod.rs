@@ -1,5 +1,24 @@
 //! Parses script files and turns them into [`ScriptStep`]s
 
+/// Like ``ws!`` but also skips comments which start with a ``#`` and go to the end of the line.
+macro_rules! ws_comments (
+    ($i:expr, $($args:tt)*) => (
+        {
+            use nom::{Convert, Err};
+
+            match sep!($i, ::script::parser::space, $($args)*) {
+                Err(e) => Err(e),
+                Ok((i1, o)) => {
+                    match ::script::parser::space(i1) {
+                        Err(e) => Err(Err::convert(e)),
+                        Ok((i2, _)) => Ok((i2, o))
+                    }
+                }
+            }
+        }
+    )
+);
+
 mod dialogue;
 mod visible;
 mod spawn;
@@ -10,9 +29,18 @@ mod stage;
 mod end;
 mod goto;
 mod play;
+mod choice;
+mod expression;
+mod condition;
+mod wait;
 
 use indexmap::IndexMap;
-use nom::types::CompleteStr;
+use nom::{
+    Err as NomErr,
+    Context,
+    IResult,
+    types::CompleteStr,
+};
 use self::{
     dialogue::{dialogue, dialogue_continue},
     visible::{show, hide},
@@ -21,34 +49,103 @@ use self::{
     move_p::move_p,
     stage::stage,
     end::end,
-    goto::goto,
-    play::play,
+    goto::{goto, call, ret},
+    play::{play, stop, sfx, volume},
+    choice::choice,
+    condition::{set, conditional},
+    wait::{wait, auto},
 };
-use super::ScriptStep;
-use super::super::error::ScriptImportError;
-
-named!(translate_to_step(CompleteStr) -> Vec<ScriptStep>,
-    many0!(
-        ws!(
-            alt_complete!(
-                dialogue
-                | dialogue_continue
-                | show | hide
-                | spawn | kill
-                | move_p
-                | stage
-                | end
-                | goto
-                | play
-            )
+use self::values::space;
+use super::{
+    ScriptStep,
+    Source,
+};
+use super::super::error::{
+    ScriptImportError,
+    ScriptParseError,
+};
+
+/// The commands of the script and how they are used.
+/// This is used to give a hint about what was expected when a script can't be parsed.
+const COMMANDS: &[(&str, &str)] = &[
+    ("SPAWN", "SPAWN 'Character' [as 'Entity'] [at (x, y)] [with 'Transition']"),
+    ("KILL", "KILL 'Entity' [with 'Transition']"),
+    ("SHOW", "SHOW 'Entity' [~ 'State'] [with 'Transition']"),
+    ("HIDE", "HIDE 'Entity' [with 'Transition']"),
+    ("MOVE", "MOVE 'Entity' (x, y)"),
+    ("STAGE", "STAGE 'Background'"),
+    ("PLAY", "PLAY 'Music' [fade seconds]"),
+    ("STOP", "STOP [fade seconds]"),
+    ("SFX", "SFX 'Sound'"),
+    ("VOLUME", "VOLUME master|music|sfx|voice 0.5"),
+    ("SET", "SET variable = expression"),
+    ("IF", "IF condition ... [ELSE ...] ENDIF"),
+    ("CHOICE", "CHOICE \"Option\" -> script:anchor ... ENDCHOICE"),
+    ("WAIT", "WAIT seconds"),
+    ("AUTO", "AUTO seconds | AUTO OFF"),
+    ("CALL", "CALL script:anchor"),
+    ("RETURN", "RETURN"),
+    ("END", "END"),
+];
+
+named!(step(CompleteStr) -> ScriptStep,
+    alt_complete!(
+        choice
+        | dialogue
+        | dialogue_continue
+        | show | hide
+        | spawn | kill
+        | move_p
+        | stage
+        | end
+        | goto
+        | play
+        | stop
+        | sfx
+        | volume
+        | set
+        | call
+        | ret
+        | wait
+        | auto
+    )
+);
+
+/// Gives how much of the text is left without using any of it.
+/// The steps are kept with this so that the line that they are on can be found once the
+/// whole text has been parsed.
+fn remaining(input: CompleteStr) -> IResult<CompleteStr, usize> {
+    Ok((input, input.0.len()))
+}
+
+// This is kept out of the ws_comments! in translate_to_step as alt_complete! inside of it
+// backtracks from errors that are returned using return_error!.
+named!(statement(CompleteStr) -> Vec<(usize, ScriptStep)>,
+    alt_complete!(
+        conditional
+        | do_parse!(
+            at: remaining >>
+            s: step >>
+            (vec![(at, s)])
         )
     )
 );
 
-named!(translate_script(CompleteStr) -> IndexMap<String, Vec<ScriptStep>>,
+named!(translate_to_step(CompleteStr) -> Vec<(usize, ScriptStep)>,
+    map!(
+        many0!(
+            ws_comments!(
+                statement
+            )
+        ),
+        |list| list.into_iter().flat_map(|steps| steps).collect()
+    )
+);
+
+named!(translate_script(CompleteStr) -> IndexMap<String, Vec<(usize, ScriptStep)>>,
     map!(
         many0!(
-            ws!(
+            ws_comments!(
                 pair!(
                     opt!(
                         preceded!(
@@ -58,10 +155,14 @@ named!(translate_script(CompleteStr) -> IndexMap<String, Vec<ScriptStep>>,
                     ),
                     terminated!(
                         translate_to_step,
-                        peek!(
-                            alt_complete!(
-                                tag!(":")
-                                | eof!()
+                        // Anything else means that a step couldn't be parsed
+                        // so the error shouldn't be backtracked from.
+                        return_error!(
+                            peek!(
+                                alt_complete!(
+                                    tag!(":")
+                                    | eof!()
+                                )
                             )
                         )
                     )
@@ -80,22 +181,150 @@ named!(translate_script(CompleteStr) -> IndexMap<String, Vec<ScriptStep>>,
 );
 
 /// Translates a [`str`] into a [`Vec`] of [`ScriptSteps`].
-/// Returns an error if it fails.
+/// Returns an error with the line and column of the step that couldn't be parsed if it fails.
 pub fn translate(text: &str)
     -> Result<IndexMap<String, Vec<ScriptStep>>, ScriptImportError> {
+    translate_with_source(text).map(|(map, _)| map)
+}
+
+/// Like [`translate`] but also gives the line that each step is on.
+pub fn translate_with_source(text: &str)
+    -> Result<(IndexMap<String, Vec<ScriptStep>>, Source), ScriptImportError> {
     match translate_script(CompleteStr(text)) {
-        Ok((_, map)) => Ok(map),
-        Err(e) => Err(ScriptImportError::Nom(e.into_error_kind())),
+        Ok((rest, map)) => {
+            if rest.trim().is_empty() {
+                let mut source = Source::default();
+                let map = map.into_iter()
+                    .map(|(anchor, steps)| {
+                        let (lines, steps): (Vec<usize>, Vec<ScriptStep>) = steps.into_iter()
+                            .map(|(at, step)| (line_of(text, text.len() - at), step))
+                            .unzip();
+                        source.lines.insert(anchor.clone(), lines);
+                        (anchor, steps)
+                    })
+                    .collect();
+                Ok((map, source))
+            } else {
+                Err(parse_error(text, rest.0).into())
+            }
+        },
+        Err(NomErr::Error(Context::Code(rest, _))) | Err(NomErr::Failure(Context::Code(rest, _))) => {
+            Err(parse_error(text, rest.0).into())
+        },
+        Err(NomErr::Incomplete(_)) => Err(parse_error(text, "").into()),
+    }
+}
+
+/// The line that ``offset`` is on in ``text``. (Starts from 1)
+fn line_of(text: &str, offset: usize) -> usize {
+    text[..offset].matches('\n').count() + 1
+}
+
+/// Creates an error pointing at the start of ``rest`` which is the part of ``text`` that
+/// couldn't be parsed.
+fn parse_error(text: &str, rest: &str) -> ScriptParseError {
+    let rest = match space(CompleteStr(rest)) {
+        Ok((rest, _)) => rest.0,
+        Err(_) => rest,
+    };
+    let offset = text.len() - rest.len();
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let line_end = text[offset..].find('\n').map(|i| offset + i).unwrap_or(text.len());
+    let anchor = before.lines()
+        .filter_map(|l| {
+            let l = l.trim();
+            if l.starts_with(':') {
+                l[1..].split_whitespace().next()
+            } else { None }
+        })
+        .last()
+        .unwrap_or("main");
+    let in_choice = before.lines()
+        .map(|l| l.trim())
+        .filter(|l| l.starts_with("CHOICE") || l.starts_with("ENDCHOICE"))
+        .last()
+        .map_or(false, |l| l.starts_with("CHOICE"));
+    ScriptParseError {
+        file: None,
+        anchor: anchor.to_string(),
+        line: line_of(text, offset),
+        column: text[line_start..offset].chars().count() + 1,
+        text: text[line_start..line_end].trim_end().to_string(),
+        expected: expected(rest, in_choice),
+    }
+}
+
+/// Gives a hint about what was expected at the start of ``rest``.
+fn expected(rest: &str, in_choice: bool) -> String {
+    if rest.is_empty() {
+        return "unexpected end of the script (is an ENDIF or ENDCHOICE missing?)".to_string()
+    }
+    if in_choice {
+        return "expected an option like \"Option\" -> script:anchor or ENDCHOICE".to_string()
+    }
+    let word: String = rest.chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect();
+    match COMMANDS.iter().find(|(name, _)| *name == word) {
+        Some((_, usage)) => format!("expected {}", usage),
+        None => {
+            let names: Vec<&str> = COMMANDS.iter()
+                .map(|(name, _)| *name)
+                .collect();
+            format!("expected a dialogue, {}, -> or an :anchor", names.join(", "))
+        },
     }
 }
 
 #[test]
 pub fn try_translate() {
     println!("{:?}", translate_script(CompleteStr(r#"
+        # A comment
         "wow"
-        "Much wow indeed"
+        "Much wow indeed" # A comment after a step
         :anchor2
-        "That's a different anchor"
+        SET met_aiko = true
+        IF met_aiko
+            "That's a different anchor"
+        ENDIF
         "I'm impressed"
+        END
     "#)));
-}
\ No newline at end of file
+}
+#[test]
+fn test_translate_error() {
+    let script = r#"
+        "wow"
+        :anchor2
+        IF met_aiko
+            SPAWN Aiko
+        ENDIF
+    "#;
+    match translate(script) {
+        Err(ScriptImportError::Parse(e)) => {
+            println!("{}", e);
+            assert_eq!((e.line, e.column), (5, 13));
+            assert_eq!(e.anchor, "anchor2");
+        },
+        other => panic!("Expected a parse error, got {:?}", other),
+    }
+}
+#[test]
+fn test_translate_lines() {
+    let (map, source) = translate_with_source(r#"
+        "wow"
+        # A comment
+        :anchor2
+        IF met_aiko
+            "Hi"
+        ELSE
+
+            "Bye"
+        ENDIF
+        END
+    "#).unwrap();
+    assert_eq!(map["anchor2"].len(), 5);
+    assert_eq!(source.lines["main"], vec![2]);
+    assert_eq!(source.lines["anchor2"], vec![5, 6, 7, 9, 11]);
+}
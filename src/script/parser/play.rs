@@ -1,17 +1,92 @@
 use super::{
     ScriptStep,
     values::quote,
+    expression::identifier,
 };
-use nom::types::CompleteStr;
+use super::super::super::music::volume::Channel;
+use nom::{
+    double,
+    types::CompleteStr,
+};
+
+named!(fade(CompleteStr) -> Option<f64>,
+    opt!(
+        ws!(
+            preceded!(
+                tag!("fade"),
+                double
+            )
+        )
+    )
+);
 
 named!(pub play(CompleteStr) -> ScriptStep,
     map!(
         preceded!(
             tag!("PLAY"),
+            pair!(
+                ws!(
+                    quote
+                ),
+                fade
+            )
+        ),
+        |(name, fade)| ScriptStep::Play(name.to_string(), fade)
+    )
+);
+
+named!(pub stop(CompleteStr) -> ScriptStep,
+    map!(
+        preceded!(
+            tag!("STOP"),
+            fade
+        ),
+        |fade| ScriptStep::Stop(fade)
+    )
+);
+
+named!(pub sfx(CompleteStr) -> ScriptStep,
+    map!(
+        preceded!(
+            tag!("SFX"),
             ws!(
                 quote
             )
         ),
-        |name| ScriptStep::Play(name.to_string())
+        |name| ScriptStep::Sfx(name.to_string())
+    )
+);
+
+named!(pub volume(CompleteStr) -> ScriptStep,
+    map!(
+        preceded!(
+            tag!("VOLUME"),
+            ws!(
+                pair!(
+                    map_opt!(identifier, |name: CompleteStr| Channel::from_name(&name)),
+                    double
+                )
+            )
+        ),
+        |(channel, volume)| ScriptStep::Volume(channel, volume as f32)
     )
-);
\ No newline at end of file
+);
+
+#[test]
+fn test_play() {
+    assert_eq!(play(CompleteStr("PLAY 'theme'")), Ok((CompleteStr(""), ScriptStep::Play("theme".to_string(), None))));
+    assert_eq!(play(CompleteStr("PLAY 'theme' fade 2.0")), Ok((CompleteStr(""), ScriptStep::Play("theme".to_string(), Some(2.)))));
+    assert_eq!(stop(CompleteStr("STOP")), Ok((CompleteStr(""), ScriptStep::Stop(None))));
+    assert_eq!(stop(CompleteStr("STOP fade 1.0")), Ok((CompleteStr(""), ScriptStep::Stop(Some(1.)))));
+}
+
+#[test]
+fn test_volume() {
+    assert_eq!(volume(CompleteStr("VOLUME music 0.5")), Ok((CompleteStr(""), ScriptStep::Volume(Channel::Music, 0.5))));
+    assert!(volume(CompleteStr("VOLUME drums 0.5")).is_err());
+}
+
+#[test]
+fn test_sfx() {
+    assert_eq!(sfx(CompleteStr("SFX 'door_slam'")), Ok((CompleteStr(""), ScriptStep::Sfx("door_slam".to_string()))));
+}
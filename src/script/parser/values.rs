@@ -1,21 +1,23 @@
-use nom::types::CompleteStr;
+use nom::{
+    IResult,
+    Err,
+    Context,
+    ErrorKind,
+    multispace,
+    types::CompleteStr,
+};
 use std::str::FromStr;
 
-named!(pub quote(CompleteStr) -> CompleteStr,
+named!(pub quote(CompleteStr) -> String,
     ws!(
-        delimited!(
-            tag!("'"),
-            take_until!("'"),
-            tag!("'")
-        )
+        call!(quoted, '\'')
     )
 );
 
-named!(pub speech(CompleteStr) -> CompleteStr,
-    delimited!(
-        tag!("\""),
-        take_until!("\""),
-        tag!("\"")
+named!(pub speech(CompleteStr) -> String,
+    alt_complete!(
+        triple_quoted
+        | call!(quoted, '"')
     )
 );
 
@@ -39,7 +41,103 @@ named!(pub pos(CompleteStr) -> (f64, f64),
     )
 );
 
+named!(comment(CompleteStr) -> CompleteStr,
+    recognize!(
+        preceded!(
+            tag!("#"),
+            take_till!(|c| c == '\n')
+        )
+    )
+);
+
+named!(pub space(CompleteStr) -> CompleteStr,
+    recognize!(
+        many0!(
+            alt_complete!(
+                multispace
+                | comment
+            )
+        )
+    )
+);
+
+/// Parses text between two ``delimiter``s.
+///
+/// A backslash escapes the character after it so ``\"``, ``\'`` and ``\\`` can be used to write
+/// the quote characters and backslashes. ``\n`` is a new line.
+fn quoted(input: CompleteStr, delimiter: char) -> IResult<CompleteStr, String> {
+    if !input.starts_with(delimiter) {
+        return Err(Err::Error(Context::Code(input, ErrorKind::Tag)))
+    }
+    let start = delimiter.len_utf8();
+    match unescape(&input[start..], &delimiter.to_string()) {
+        Some((text, len)) => Ok((CompleteStr(&input[start + len..]), text)),
+        None => Err(Err::Error(Context::Code(input, ErrorKind::Tag))),
+    }
+}
+
+/// Parses text between ``"""`` which can go over multiple lines.
+///
+/// Every line is trimmed so that it can be indented along with the rest of the script
+/// and any empty lines at the start and the end are removed.
+fn triple_quoted(input: CompleteStr) -> IResult<CompleteStr, String> {
+    const DELIMITER: &str = "\"\"\"";
+    if !input.starts_with(DELIMITER) {
+        return Err(Err::Error(Context::Code(input, ErrorKind::Tag)))
+    }
+    let start = DELIMITER.len();
+    match unescape(&input[start..], DELIMITER) {
+        Some((text, len)) => {
+            let lines: Vec<&str> = text.lines()
+                .map(|l| l.trim())
+                .skip_while(|l| l.is_empty())
+                .collect();
+            let end = lines.iter().rposition(|l| !l.is_empty()).map(|i| i + 1).unwrap_or(0);
+            Ok((CompleteStr(&input[start + len..]), lines[..end].join("\n")))
+        },
+        None => Err(Err::Error(Context::Code(input, ErrorKind::Tag))),
+    }
+}
+
+/// Reads ``text`` until ``delimiter`` is found while replacing escaped characters.
+/// Returns the text and the length of what was read including the delimiter.
+fn unescape(text: &str, delimiter: &str) -> Option<(String, usize)> {
+    let mut result = String::new();
+    let mut chars = text.char_indices();
+    while let Some((i, c)) = chars.next() {
+        if text[i..].starts_with(delimiter) {
+            return Some((result, i + delimiter.len()))
+        }
+        if c == '\\' {
+            match chars.next() {
+                Some((_, 'n')) => result.push('\n'),
+                Some((_, e)) if e == '\\' || e == '"' || e == '\'' => result.push(e),
+                // Anything else isn't an escape so the backslash is kept.
+                Some((_, e)) => {
+                    result.push('\\');
+                    result.push(e);
+                },
+                None => result.push('\\'),
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    None
+}
+
 #[test]
 fn parser_value_pos() {
     println!("{:?}", pos(CompleteStr("( 5 , 2 )")))
-}
\ No newline at end of file
+}
+
+#[test]
+fn parser_value_text() {
+    assert_eq!(speech(CompleteStr(r#""She said \"hi\"\nthen left\\""#)),
+        Ok((CompleteStr(""), "She said \"hi\"\nthen left\\".to_string())));
+    assert_eq!(quote(CompleteStr(r#"'Aiko\'s Cat'"#)),
+        Ok((CompleteStr(""), "Aiko's Cat".to_string())));
+    assert_eq!(speech(CompleteStr("\"\"\"\n    First line\n    Second line\n\"\"\"")),
+        Ok((CompleteStr(""), "First line\nSecond line".to_string())));
+    println!("{:?}", space(CompleteStr("  # A comment\n  # Another comment\n\"Dialogue\"")));
+}
@@ -0,0 +1,39 @@
+use nom::{
+    double,
+    types::CompleteStr,
+};
+use super::super::ScriptStep;
+
+named!(pub wait(CompleteStr) -> ScriptStep,
+    map!(
+        preceded!(
+            tag!("WAIT"),
+            ws!(
+                double
+            )
+        ),
+        |time| ScriptStep::Wait(time)
+    )
+);
+
+named!(pub auto(CompleteStr) -> ScriptStep,
+    map!(
+        preceded!(
+            tag!("AUTO"),
+            ws!(
+                alt_complete!(
+                    value!(None, tag!("OFF"))
+                    | map!(double, Some)
+                )
+            )
+        ),
+        |time| ScriptStep::Auto(time)
+    )
+);
+
+#[test]
+fn test_wait() {
+    assert_eq!(wait(CompleteStr("WAIT 1.5")), Ok((CompleteStr(""), ScriptStep::Wait(1.5))));
+    assert_eq!(auto(CompleteStr("AUTO 2")), Ok((CompleteStr(""), ScriptStep::Auto(Some(2.)))));
+    assert_eq!(auto(CompleteStr("AUTO OFF")), Ok((CompleteStr(""), ScriptStep::Auto(None))));
+}
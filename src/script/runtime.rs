@@ -0,0 +1,238 @@
+//! Runs the steps of a [`Script`] without knowing how anything is drawn or played.
+//!
+//! Anything that uses a [`Script`] to tell a story implements [`Runtime`] which is what
+//! the steps use to change what the player sees and hears.
+//! [`Game`](../../game/struct.Game.html) implements it using textures, textboxes and Rodio
+//! while [`Headless`](../headless/struct.Headless.html) only records the names of everything
+//! so that stories can be tested using ``cargo test``.
+
+use super::{
+    Script,
+    ScriptStep,
+};
+use super::super::music::volume::Channel;
+
+/// Something that can run the steps of a [`Script`].
+///
+/// The steps that only change the [`Script`] (like ``SET``, ``IF`` and ``->``) are handled
+/// for you so only the things the player sees and hears need to be implemented.
+pub trait Runtime {
+    /// The script that is being run.
+    fn script(&mut self) -> &mut Script;
+    /// Shows dialogue with the variables already put into it.
+    /// The ``speaker`` is ``None`` if the speaker is the same as the last dialogue.
+    fn show_dialogue(&mut self, speaker: Option<String>, text: String);
+    /// Called after dialogue has been shown and the story has stopped on it.
+    fn dialogue_shown(&mut self) {}
+    /// Shows the options of a choice the player has to make.
+    fn show_choices(&mut self, options: Vec<String>);
+    /// Spawns an entity of a character onto the stage.
+    fn spawn(&mut self, character: &str, entity: &str, pos: Option<(f64, f64)>, transition: Option<&str>);
+    /// Removes an entity from the stage.
+    fn kill(&mut self, entity: &str, transition: Option<&str>);
+    /// Shows an entity, maybe changing its state.
+    fn show(&mut self, entity: &str, state: Option<&str>, transition: Option<&str>);
+    /// Hides an entity.
+    fn hide(&mut self, entity: &str, transition: Option<&str>);
+    /// Moves an entity to a position on the grid.
+    fn move_entity(&mut self, entity: &str, pos: (f64, f64));
+    /// Sets the background.
+    fn set_background(&mut self, name: &str);
+    /// Plays music, crossfading from the music that is playing over ``fade`` seconds.
+    fn play_music(&mut self, name: &str, fade: Option<f64>);
+    /// Stops the music, fading it out over ``fade`` seconds.
+    fn stop_music(&mut self, fade: Option<f64>);
+    /// Plays a sound effect once.
+    fn play_sfx(&mut self, name: &str);
+    /// Sets the volume of a channel of audio from 0 to 1.
+    fn set_volume(&mut self, channel: Channel, volume: f32);
+    /// Plays the voice line of the dialogue that is being shown.
+    fn play_voice(&mut self, name: &str);
+    /// Stops the voice line of the last dialogue when the story continues.
+    fn stop_voice(&mut self);
+}
+
+/// Runs the steps of the script until one of them stops, like dialogue or a choice.
+pub fn next_step<R: Runtime>(runtime: &mut R) {
+    runtime.script().timer = None;
+    runtime.stop_voice();
+    loop {
+        let step = {
+            let script = runtime.script();
+            // The length is checked every time as a step can change the current script.
+            if script.step < script.script.len() {
+                script.step += 1;
+                script.current = script.step - 1;
+                script.script.get(script.step - 1).cloned()
+            } else {
+                script.next_script();
+                continue
+            }
+        };
+        if let Some(step) = step {
+            if !execute_step(runtime, step) {
+                break
+            }
+        }
+    }
+}
+
+/// Picks one of the options of the choice the player is currently making
+/// and continues the story from where that option goes to.
+/// Returns ``false`` if there isn't an option at that index.
+pub fn select_choice<R: Runtime>(runtime: &mut R, index: usize) -> bool {
+    let option = runtime.script().choices.get(index).cloned();
+    match option {
+        Some((name, anchor)) => {
+            runtime.script().choices.clear();
+            runtime.show_choices(Vec::new());
+            runtime.script().go_to(name, anchor);
+            next_step(runtime);
+            true
+        },
+        None => false,
+    }
+}
+
+/// Runs a step. Returns whether the next step should be run straight away.
+pub fn execute_step<R: Runtime>(runtime: &mut R, step: ScriptStep) -> bool {
+    // boolean value is whether to continue or not.
+    // True => Continue
+    // False => Stop
+    use self::ScriptStep::*;
+    match step {
+        Dialogue(speaker, content, voice) => {
+            let (speaker_text, text) = {
+                let script = runtime.script();
+                let texts = (script.interpolate(&speaker), script.interpolate(&content));
+                script.speaker = speaker;
+                script.timer = script.auto_advance;
+                texts
+            };
+            runtime.show_dialogue(Some(speaker_text), text);
+            if let Some(voice) = voice {
+                runtime.play_voice(&voice);
+            }
+            runtime.dialogue_shown();
+            false
+        },
+        DialogueContinue(content, voice) => {
+            let text = {
+                let script = runtime.script();
+                script.timer = script.auto_advance;
+                script.interpolate(&content)
+            };
+            runtime.show_dialogue(None, text);
+            if let Some(voice) = voice {
+                runtime.play_voice(&voice);
+            }
+            runtime.dialogue_shown();
+            false
+        },
+        Show(entity, state, trans) => {
+            runtime.show(&entity, state.as_ref().map(|s| s.as_str()), trans.as_ref().map(|s| s.as_str()));
+            true
+        },
+        Hide(entity, trans) => {
+            runtime.hide(&entity, trans.as_ref().map(|s| s.as_str()));
+            true
+        },
+        Spawn(character, entity, pos, trans) => {
+            let name = entity.unwrap_or_else(|| character.clone());
+            runtime.spawn(&character, &name, pos, trans.as_ref().map(|s| s.as_str()));
+            true
+        },
+        Kill(entity, trans) => {
+            runtime.kill(&entity, trans.as_ref().map(|s| s.as_str()));
+            true
+        },
+        Move(entity, pos) => {
+            runtime.move_entity(&entity, pos);
+            true
+        },
+        Stage(bg) => {
+            runtime.set_background(&bg);
+            true
+        },
+        GoTo(name, anchor) => {
+            runtime.script().go_to(name, anchor);
+            true
+        },
+        Call(name, anchor) => {
+            let script = runtime.script();
+            // The step has already been moved past the CALL.
+            let (script_index, anchor_index) = script.index;
+            script.calls.push((script_index, anchor_index, script.step));
+            script.go_to(name, anchor);
+            true
+        },
+        Return => {
+            let script = runtime.script();
+            if let Some((script_index, anchor, step)) = script.calls.pop() {
+                script.set_position((script_index, anchor), step);
+                true
+            } else {
+                script.step -= 1;
+                false
+            }
+        },
+        Choice(options) => {
+            let mut texts = Vec::new();
+            {
+                let script = runtime.script();
+                script.choices.clear();
+                for (text, name, anchor) in options {
+                    texts.push(text);
+                    script.choices.push((name, anchor));
+                }
+            }
+            runtime.show_choices(texts);
+            false
+        },
+        Set(name, expr) => {
+            let script = runtime.script();
+            let value = expr.evaluate(&script.variables);
+            script.variables.insert(name, value);
+            true
+        },
+        If(condition, skip) => {
+            let script = runtime.script();
+            if !condition.evaluate(&script.variables).is_true() {
+                script.step += skip;
+            }
+            true
+        },
+        Else(skip) => {
+            runtime.script().step += skip;
+            true
+        },
+        Play(name, fade) => {
+            runtime.play_music(&name, fade);
+            true
+        },
+        Stop(fade) => {
+            runtime.stop_music(fade);
+            true
+        },
+        Sfx(name) => {
+            runtime.play_sfx(&name);
+            true
+        },
+        Volume(channel, volume) => {
+            runtime.set_volume(channel, volume);
+            true
+        },
+        Wait(time) => {
+            runtime.script().timer = Some(time);
+            false
+        },
+        Auto(time) => {
+            runtime.script().auto_advance = time;
+            true
+        },
+        End => {
+            runtime.script().step -= 1;
+            false
+        }
+    }
+}
@@ -0,0 +1,299 @@
+//! Checks that the names used by the scripts exist before the story is played.
+//!
+//! While the story is being played, steps that use a character, state, background, transition,
+//! music, sound effect, voice line, script or anchor that doesn't exist are ignored.
+//! [`Script::validate`] finds these steps so they can be fixed.
+
+use std::{
+    fmt,
+    collections::{
+        HashMap,
+        HashSet,
+    },
+};
+
+use super::{
+    Script,
+    ScriptStep,
+};
+use super::super::game::Game;
+
+/// The names of everything that the scripts can use.
+#[derive(Debug, Clone, Default)]
+pub struct Names {
+    /// The characters and the names of their states.
+    pub characters: HashMap<String, HashSet<String>>,
+    /// The names of the backgrounds.
+    pub backgrounds: HashSet<String>,
+    /// The names of the character transitions.
+    pub transitions: HashSet<String>,
+    /// The names of the music.
+    pub music: HashSet<String>,
+    /// The names of the sound effects.
+    pub sfx: HashSet<String>,
+    /// The names of the voice lines.
+    pub voice: HashSet<String>,
+}
+impl Names {
+    /// Create a new [`Names`] struct without any names.
+    pub fn new() -> Names {
+        Names::default()
+    }
+    /// Gets the names of everything that has been loaded into a [`Game`].
+    pub fn from_game(game: &Game) -> Names {
+        Names {
+            characters: game.characters.iter()
+                .map(|(name, character)| (name.to_string(), character.state_map.keys().cloned().collect()))
+                .collect(),
+            backgrounds: game.backgrounds.keys().cloned().collect(),
+            transitions: game.anims.char_trans.keys().cloned().collect(),
+            music: match game.music {
+                Some(ref m) => m.library.keys().cloned().collect(),
+                None => HashSet::new(),
+            },
+            sfx: match game.music {
+                Some(ref m) => m.sfx.library.keys().cloned().collect(),
+                None => HashSet::new(),
+            },
+            voice: match game.music {
+                Some(ref m) => m.voice.library.keys().cloned().collect(),
+                None => HashSet::new(),
+            },
+        }
+    }
+}
+
+/// What is wrong with a step.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    /// The character doesn't exist.
+    UnknownCharacter(String),
+    /// None of the characters that the entity is spawned from have the state.
+    /// (Entity, State)
+    UnknownState(String, String),
+    /// The entity is never spawned by any of the scripts.
+    UnknownEntity(String),
+    /// The background doesn't exist.
+    UnknownBackground(String),
+    /// The transition doesn't exist.
+    UnknownTransition(String),
+    /// The music doesn't exist.
+    UnknownMusic(String),
+    /// The sound effect doesn't exist.
+    UnknownSfx(String),
+    /// The voice line doesn't exist.
+    UnknownVoice(String),
+    /// The script that is being gone to doesn't exist.
+    UnknownScript(String),
+    /// The anchor that is being gone to doesn't exist in the script.
+    /// (Script, Anchor)
+    UnknownAnchor(String, String),
+}
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Problem::UnknownCharacter(ref c) => write!(f, "unknown character '{}'", c),
+            Problem::UnknownState(ref e, ref s) => write!(f, "entity '{}' doesn't have a state called '{}'", e, s),
+            Problem::UnknownEntity(ref e) => write!(f, "entity '{}' is never spawned", e),
+            Problem::UnknownBackground(ref b) => write!(f, "unknown background '{}'", b),
+            Problem::UnknownTransition(ref t) => write!(f, "unknown transition '{}'", t),
+            Problem::UnknownMusic(ref m) => write!(f, "unknown music '{}'", m),
+            Problem::UnknownSfx(ref s) => write!(f, "unknown sound effect '{}'", s),
+            Problem::UnknownVoice(ref v) => write!(f, "unknown voice line '{}'", v),
+            Problem::UnknownScript(ref s) => write!(f, "unknown script '{}'", s),
+            Problem::UnknownAnchor(ref s, ref a) => write!(f, "script '{}' doesn't have an anchor called '{}'", s, a),
+        }
+    }
+}
+
+/// A problem with a step and where that step is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    /// The script that the step is in.
+    pub script: String,
+    /// The anchor that the step is in.
+    pub anchor: String,
+    /// The position of the step in the anchor. (Starts from 0)
+    pub step: usize,
+    /// The file that the script was read from.
+    /// ``None`` if the script wasn't read from a file.
+    pub file: Option<String>,
+    /// The line of the script that the step is on. (Starts from 1)
+    /// ``None`` if the script doesn't have a [`Source`], like one from a bundle.
+    pub line: Option<usize>,
+    /// What is wrong with the step.
+    pub problem: Problem,
+}
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let file = match self.file {
+            Some(ref e) => e.as_str(),
+            None => self.script.as_str(),
+        };
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {} (in anchor '{}')", file, line, self.problem, self.anchor),
+            None => write!(f, "{}: {} (in anchor '{}', step {})", file, self.problem, self.anchor, self.step + 1),
+        }
+    }
+}
+
+impl Script {
+    /// Checks every step of every script against the names that can be used.
+    /// Returns a [`Diagnostic`] for every problem that is found.
+    pub fn validate(&self, names: &Names) -> Vec<Diagnostic> {
+        // The characters that each entity can be spawned from.
+        let mut entities: HashMap<&str, HashSet<&str>> = HashMap::new();
+        for anchors in self.scripts.values() {
+            for steps in anchors.values() {
+                for step in steps {
+                    if let ScriptStep::Spawn(ref character, ref entity, _, _) = *step {
+                        let name = entity.as_ref().unwrap_or(character);
+                        entities.entry(name.as_str())
+                            .or_insert_with(HashSet::new)
+                            .insert(character.as_str());
+                    }
+                }
+            }
+        }
+        let mut diagnostics = Vec::new();
+        for (script, anchors) in self.scripts.iter() {
+            let source = self.sources.get(script);
+            for (anchor, steps) in anchors.iter() {
+                for (i, step) in steps.iter().enumerate() {
+                    let mut problems = Vec::new();
+                    self.check_step(step, script, names, &entities, &mut problems);
+                    diagnostics.extend(problems.into_iter().map(|problem| Diagnostic {
+                        script: script.to_string(),
+                        anchor: anchor.to_string(),
+                        step: i,
+                        file: source.and_then(|s| s.file.clone()),
+                        line: source.and_then(|s| s.line(anchor, i)),
+                        problem,
+                    }));
+                }
+            }
+        }
+        diagnostics
+    }
+    /// Adds the problems with a step in ``script``.
+    fn check_step(&self, step: &ScriptStep, script: &str, names: &Names,
+                  entities: &HashMap<&str, HashSet<&str>>, problems: &mut Vec<Problem>) {
+        use self::ScriptStep::*;
+        let entity = |name: &str, problems: &mut Vec<Problem>| {
+            if !entities.contains_key(name) {
+                problems.push(Problem::UnknownEntity(name.to_string()));
+            }
+        };
+        let transition = |t: &Option<String>, problems: &mut Vec<Problem>| {
+            if let Some(ref t) = *t {
+                if !names.transitions.contains(t) {
+                    problems.push(Problem::UnknownTransition(t.to_string()));
+                }
+            }
+        };
+        match *step {
+            Show(ref e, ref state, ref t) => {
+                entity(e, problems);
+                if let (Some(state), Some(characters)) = (state.as_ref(), entities.get(e.as_str())) {
+                    let known = characters.iter()
+                        .any(|c| names.characters.get(*c).map_or(false, |states| states.contains(state)));
+                    // Unknown characters are already reported by the SPAWN.
+                    let spawnable = characters.iter().any(|c| names.characters.contains_key(*c));
+                    if spawnable && !known {
+                        problems.push(Problem::UnknownState(e.to_string(), state.to_string()));
+                    }
+                }
+                transition(t, problems);
+            },
+            Hide(ref e, ref t) | Kill(ref e, ref t) => {
+                entity(e, problems);
+                transition(t, problems);
+            },
+            Spawn(ref c, _, _, ref t) => {
+                if !names.characters.contains_key(c) {
+                    problems.push(Problem::UnknownCharacter(c.to_string()));
+                }
+                transition(t, problems);
+            },
+            Move(ref e, _) => entity(e, problems),
+            Stage(ref bg) => if !names.backgrounds.contains(bg) {
+                problems.push(Problem::UnknownBackground(bg.to_string()));
+            },
+            Play(ref m, _) => if !names.music.contains(m) {
+                problems.push(Problem::UnknownMusic(m.to_string()));
+            },
+            Sfx(ref s) => if !names.sfx.contains(s) {
+                problems.push(Problem::UnknownSfx(s.to_string()));
+            },
+            Dialogue(_, _, Some(ref v)) | DialogueContinue(_, Some(ref v)) => if !names.voice.contains(v) {
+                problems.push(Problem::UnknownVoice(v.to_string()));
+            },
+            GoTo(ref s, ref a) | Call(ref s, ref a) => self.check_target(script, s, a, problems),
+            Choice(ref options) => for &(_, ref s, ref a) in options {
+                self.check_target(script, s, a, problems);
+            },
+            Dialogue(..) | DialogueContinue(..) | Return | Set(..) | If(..) | Else(_)
+            | Stop(_) | Volume(..) | Wait(_) | Auto(_) | End => {},
+        }
+    }
+    /// Adds a problem if the script or anchor that is being gone to doesn't exist.
+    /// If the name of the script is ``None`` then the script is ``current``.
+    fn check_target(&self, current: &str, script: &Option<String>, anchor: &Option<String>,
+                    problems: &mut Vec<Problem>) {
+        let name = script.as_ref().map(|s| s.as_str()).unwrap_or(current);
+        match self.scripts.get(name) {
+            Some(anchors) => if let Some(ref anchor) = *anchor {
+                if !anchors.contains_key(anchor) {
+                    problems.push(Problem::UnknownAnchor(name.to_string(), anchor.to_string()));
+                }
+            },
+            None => problems.push(Problem::UnknownScript(name.to_string())),
+        }
+    }
+}
+
+impl Game {
+    /// Checks the scripts against the characters, backgrounds, transitions, music, sound effects and voice lines
+    /// that have been loaded.
+    pub fn validate_scripts(&self) -> Vec<Diagnostic> {
+        self.story.validate(&Names::from_game(self))
+    }
+}
+
+#[test]
+fn test_validate() {
+    let mut story = Script::new();
+    story.load_from_str("main".to_string(), r#"
+    :start
+        SPAWN 'Aiko' as 'A'
+        SPAWN 'Hiro'
+        SHOW 'A' ~ 'angry'
+        STAGE 'park'
+        MOVE 'B' (1.0, 1.0)
+        -> :nowhere
+    :end
+        CHOICE
+            "Back" -> :start
+            "Other" -> other:start
+        ENDCHOICE
+    "#).unwrap();
+    let mut names = Names::new();
+    names.characters.insert("Aiko".to_string(), vec!["happy".to_string()].into_iter().collect());
+    let diagnostics = story.validate(&names);
+    let problems: Vec<(String, usize, Option<usize>, Problem)> = diagnostics.iter()
+        .cloned()
+        .map(|d| (d.anchor, d.step, d.line, d.problem))
+        .collect();
+    assert_eq!(problems, vec![
+        ("start".to_string(), 1, Some(4), Problem::UnknownCharacter("Hiro".to_string())),
+        ("start".to_string(), 2, Some(5), Problem::UnknownState("A".to_string(), "angry".to_string())),
+        ("start".to_string(), 3, Some(6), Problem::UnknownBackground("park".to_string())),
+        ("start".to_string(), 4, Some(7), Problem::UnknownEntity("B".to_string())),
+        ("start".to_string(), 5, Some(8), Problem::UnknownAnchor("main".to_string(), "nowhere".to_string())),
+        ("end".to_string(), 0, Some(10), Problem::UnknownScript("other".to_string())),
+    ]);
+    assert_eq!(diagnostics[0].to_string(), "main:4: unknown character 'Hiro' (in anchor 'start')");
+    // Scripts without a source, like the ones from a bundle, still say which step it is.
+    story.sources.clear();
+    assert_eq!(story.validate(&names)[0].to_string(), "main: unknown character 'Hiro' (in anchor 'start', step 2)");
+}
@@ -0,0 +1,116 @@
+//! The settings that the player has chosen, like the volume of the music.
+//!
+//! The settings are kept in their own file in the save directory which isn't part of any
+//! save slot so that they are the same every time the game is started.
+
+use std::{
+    fs::{
+        self,
+        File,
+    },
+    io::{
+        Read,
+        Write,
+    },
+    path::PathBuf,
+};
+use toml;
+
+use super::{
+    game::Game,
+    error::SaveError,
+    music::volume::{
+        Channel,
+        Volume,
+    },
+};
+
+/// The name of the file in the save directory that the settings are kept in.
+pub const SETTINGS_FILE: &str = "settings.toml";
+
+/// The settings that the player has chosen.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    /// The volume of each channel of audio.
+    #[serde(default)]
+    pub volume: Volume,
+    /// The file that the settings are written to every time they change.
+    /// ``None`` means that the settings aren't written anywhere.
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+impl Settings {
+    /// Create new [`Settings`] with everything at their defaults.
+    pub fn new() -> Settings {
+        Settings::default()
+    }
+    /// Reads the settings from a [`&str`] using TOML.
+    /// Settings that are missing are set to their defaults.
+    pub fn from_toml(text: &str) -> Result<Settings, SaveError> {
+        Ok(toml::from_str(text)?)
+    }
+    /// Turns the settings into a [`String`] using TOML.
+    pub fn to_toml(&self) -> Result<String, SaveError> {
+        Ok(toml::to_string(self)?)
+    }
+}
+
+impl Game {
+    /// Sets the volume of a channel from 0 to 1 and writes the settings to [`Settings::path`].
+    /// The audio that is playing changes straight away.
+    pub fn set_volume(&mut self, channel: Channel, volume: f32) {
+        self.settings.volume.set(channel, volume);
+        if let Some(ref mut m) = self.music {
+            m.set_volumes(self.settings.volume);
+        }
+        // Losing a setting isn't worth stopping the game for.
+        let _ = self.save_settings();
+    }
+    /// Writes the settings to [`Settings::path`] if it is set.
+    pub fn save_settings(&self) -> Result<(), SaveError> {
+        if let Some(ref path) = self.settings.path {
+            let text = self.settings.to_toml()?;
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            File::create(path)?.write_all(text.as_bytes())?;
+        }
+        Ok(())
+    }
+    /// Reads the settings from [`Settings::path`] if it is set and applies them.
+    /// Nothing happens if the file doesn't exist yet.
+    pub fn load_settings(&mut self) -> Result<(), SaveError> {
+        let path = match self.settings.path {
+            Some(ref path) if path.is_file() => path.clone(),
+            _ => return Ok(()),
+        };
+        let mut buffer = String::new();
+        File::open(&path)?.read_to_string(&mut buffer)?;
+        self.settings = Settings::from_toml(&buffer)?;
+        self.settings.path = Some(path);
+        if let Some(ref mut m) = self.music {
+            m.set_volumes(self.settings.volume);
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn test_settings() {
+    let mut settings = Settings::new();
+    settings.volume.set(Channel::Music, 0.5);
+    let text = settings.to_toml().unwrap();
+    assert_eq!(Settings::from_toml(&text).unwrap(), settings);
+    let partial = Settings::from_toml("[volume]\nvoice = 0.25\n").unwrap();
+    assert_eq!(partial.volume.voice, 0.25);
+    assert_eq!(partial.volume.master, 1.);
+}
+#[test]
+fn test_script_volume() {
+    let mut game = Game::new([800., 600.]);
+    game.story.load_from_str("main".to_string(), "VOLUME music 0.2\n\"Quiet.\"").unwrap();
+    game.story.set_script("main", None);
+    game.next_step();
+    assert_eq!(game.ui.backlog.lines.back().map(|l| l.1.as_str()), Some("Quiet."));
+    assert_eq!(game.settings.volume, Volume::new());
+}
@@ -1,6 +1,12 @@
 use super::super::{
     game::Game,
     error::GameBuildError,
+    read::READ_LINES_FILE,
+    settings::{
+        Settings,
+        SETTINGS_FILE,
+    },
+    reload::ConfigKind,
 };
 use piston_window::GfxFactory;
 
@@ -27,6 +33,12 @@ pub struct GameBuilder {
     music: MaybeFile,
     /// The path or string to a Transition config file.
     transitions: MaybeFile,
+    /// The path to a script bundle or the bundle itself.
+    bundle: Option<BundleFile>,
+    /// The directory that the save slots are kept in.
+    saves: Option<String>,
+    /// Whether the config files are reloaded when they change.
+    hot_reload: bool,
 }
 impl GameBuilder {
     /// Create a new [`GameBuilder`] struct by providing the size of the window.
@@ -41,11 +53,30 @@ impl GameBuilder {
             input: None,
             music: None,
             transitions: None,
+            bundle: None,
+            saves: None,
+            hot_reload: false,
         }
     }
     /// Builds the [`Game`] or returns an error.
     pub fn build(self, factory: &mut GfxFactory) -> Result<Game, GameBuildError> {
         let mut g = Game::new(self.size);
+        if self.hot_reload {
+            let configs = [
+                (ConfigKind::Gui, &self.gui),
+                (ConfigKind::Characters, &self.characters),
+                (ConfigKind::Backgrounds, &self.backgrounds),
+                (ConfigKind::Scripts, &self.scripts),
+                (ConfigKind::Input, &self.input),
+                (ConfigKind::Music, &self.music),
+                (ConfigKind::Transitions, &self.transitions),
+            ];
+            for &(kind, file) in configs.iter() {
+                if let Some(ExtFile::Path(ref p)) = *file {
+                    g.watch_config(kind, p.as_str());
+                }
+            }
+        }
         if let Some(grid) = self.grid {
             g.apply_grid(grid[0], grid[1]);
         }
@@ -111,6 +142,16 @@ impl GameBuilder {
                 },
             }
         }
+        if let Some(bundle) = self.bundle {
+            match bundle {
+                BundleFile::Path(p) => {
+                    g.story.load_bundle_from_file(&p)?
+                },
+                BundleFile::Bytes(b) => {
+                    g.story.load_bundle(&b)?
+                },
+            }
+        }
         if let Some(e) = self.music {
             if let Ok(m) = g.enable_music() {
                 let result = match e {
@@ -141,6 +182,20 @@ impl GameBuilder {
                 return Err( GameBuildError::Transition(e) )
             }
         }
+        if let Some(dir) = self.saves {
+            g.save_dir = dir.into();
+        }
+        g.read.path = Some(g.save_dir.join(READ_LINES_FILE));
+        // Losing the read lines isn't worth stopping the game for.
+        if g.load_read_lines().is_err() {
+            g.read.lines.clear();
+        }
+        g.settings.path = Some(g.save_dir.join(SETTINGS_FILE));
+        // Settings that can't be read, like ones from an older version, are replaced by the defaults.
+        if g.load_settings().is_err() {
+            g.settings = Settings::new();
+            g.settings.path = Some(g.save_dir.join(SETTINGS_FILE));
+        }
         Ok(g)
     }
     /// Create a GUI from either a path to an external file or a [`&str`].
@@ -183,6 +238,35 @@ impl GameBuilder {
         self.transitions = Some(file.into());
         self
     }
+    /// Load scripts from a bundle file that was made using
+    /// [`compile_bundle`](../script/bundle/fn.compile_bundle.html) instead of parsing them.
+    pub fn bundle<S: ToString>(mut self, path: S) -> Self {
+        self.bundle = Some(BundleFile::Path(path.to_string()));
+        self
+    }
+    /// Load scripts from a bundle that is in memory, like one from ``include_bytes!``.
+    pub fn bundle_from_bytes<B: Into<Vec<u8>>>(mut self, bytes: B) -> Self {
+        self.bundle = Some(BundleFile::Bytes(bytes.into()));
+        self
+    }
+    /// Reload the config files and scripts when they change while the game is running.
+    /// Only config files that are paths are watched.
+    /// [`Game::hot_reload`](../game/struct.Game.html#method.hot_reload) has to be called every frame.
+    pub fn hot_reload(mut self, on: bool) -> Self {
+        self.hot_reload = on;
+        self
+    }
+    /// Set the directory that the save slots are kept in.
+    pub fn saves<S: ToString>(mut self, dir: S) -> Self {
+        self.saves = Some(dir.to_string());
+        self
+    }
+}
+
+/// Where a script bundle is read from.
+enum BundleFile {
+    Path(String),
+    Bytes(Vec<u8>),
 }
 
 /// Represents an external file OR a [`String`].
@@ -24,6 +24,10 @@ pub fn load_backgrounds_from_file<P: AsRef<Path>>(path: P, factory: &mut GfxFact
 /// Load the backgrounds from a TOML str
 pub fn load_backgrounds_from_str(text: &str, factory: &mut GfxFactory)
     -> Result<HashMap<String, Arc<G2dTexture>>, ConfigImportError> {
-    let map: HashMap<String, String> = from_str(text)?;
-    Ok(load_background_images(map, factory))
+    Ok(load_background_images(parse_backgrounds_from_str(text)?, factory))
+}
+
+/// Read the paths of the backgrounds from a TOML str without loading any of the images.
+pub fn parse_backgrounds_from_str(text: &str) -> Result<HashMap<String, String>, ConfigImportError> {
+    Ok(from_str(text)?)
 }
\ No newline at end of file
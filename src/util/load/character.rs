@@ -28,12 +28,18 @@ pub fn load_characters_from_file<P: AsRef<Path>>(path: P, window: &mut GfxFactor
 /// Load the characters from a TOML str
 pub fn load_characters_from_str(text: &str, factory: &mut GfxFactory)
     -> Result<CharacterHashmap, ConfigImportError> {
+    Ok(load_character_images(parse_characters_from_str(text)?, factory))
+}
+
+/// Read the characters from a TOML str without loading any of their images.
+pub fn parse_characters_from_str(text: &str)
+    -> Result<ParsedCharactersHashmap, ConfigImportError> {
     let map: HashMap<String,
             HashMap<String, ValueType>> = from_str(text)?;
-    Ok(raw_hashmap_to_characters(map, factory))
+    Ok(raw_hashmap_to_characters(map))
 }
 
-fn raw_hashmap_to_characters(map: RawCharactersHashmap, window: &mut GfxFactory) -> CharacterHashmap {
+fn raw_hashmap_to_characters(map: RawCharactersHashmap) -> ParsedCharactersHashmap {
     let mut new_map = HashMap::new();
     for (k, v) in map.iter() {
         let mut default = None;
@@ -93,7 +99,7 @@ fn raw_hashmap_to_characters(map: RawCharactersHashmap, window: &mut GfxFactory)
         };
         new_map.insert(k.clone(), character);
     }
-    load_character_images(new_map, window)
+    new_map
 }
 
 /// A struct that represents
@@ -30,22 +30,41 @@ pub fn load_input_from_file<P: AsRef<Path>>(path: P)
 pub fn load_input_from_str(text: &str)
     -> Result<GameInput, ConfigImportError> {
     let map: HashMap<String, Vec<InputFromFile>> = from_str(text)?;
-    let mut cont = None;
+    let mut input = GameInput::new();
     for (k, v) in map.iter() {
+        let list: HashSet<Button> = v
+            .iter()
+            .map(|item| item.to_button())
+            .collect();
         match k.to_lowercase().as_str() {
             "continue" => {
-                let list: HashSet<Button> = v
-                    .iter()
-                    .map(|item| item.to_button())
-                    .collect();
-                cont = Some(list);
+                input.continue_ = list;
+            },
+            "up" => {
+                input.up = list;
+            },
+            "down" => {
+                input.down = list;
+            },
+            "back" => {
+                input.back = list;
+            },
+            "backlog" => {
+                input.backlog = list;
+            },
+            "auto" => {
+                input.auto = list;
+            },
+            "skip" => {
+                input.skip = list;
+            },
+            "replayvoice" => {
+                input.replay_voice = list;
             },
             _ => {},
         }
     }
-    Ok(GameInput {
-        continue_: cont.unwrap_or(HashSet::new()).clone()
-    })
+    Ok(input)
 }
 
 #[test]
@@ -55,6 +74,13 @@ fn test_input_from_str() {
             "Space",
             "Left",
         ]
+        Up = ["Up"]
+        Down = ["Down"]
+        Back = ["Backspace"]
+        Backlog = ["L"]
+        Auto = ["A"]
+        Skip = ["LCtrl"]
+        ReplayVoice = ["V"]
     "#))
 }
 
@@ -13,6 +13,8 @@ pub use self::{
     character::{
         load_characters_from_file,
         load_characters_from_str,
+        parse_characters_from_str,
+        CharacterFromFile,
     },
     input::{
         load_input_from_file,
@@ -25,6 +27,7 @@ pub use self::{
     background::{
         load_backgrounds_from_file,
         load_backgrounds_from_str,
+        parse_backgrounds_from_str,
     },
     script::{
         load_scripts_from_file,
@@ -34,6 +37,9 @@ pub use self::{
     music::{
         load_music_from_file,
         load_music_from_str,
+        parse_music_from_str,
+        MusicFromFile,
+        MusicLibrary,
     },
     transition::{
         load_transitions_from_file,
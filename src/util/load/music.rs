@@ -2,24 +2,61 @@ use std::{
     fs::File,
     io::Read,
     sync::Arc,
-    path::Path,
+    path::{
+        Path,
+        PathBuf,
+    },
     collections::HashMap,
 };
 use super::super::super::error::ConfigImportError;
 use toml::from_str;
 
+/// The paths of the music, sound effects and voice lines in a Music config file.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MusicFromFile {
+    /// The paths of the music.
+    #[serde(flatten)]
+    pub music: HashMap<String, String>,
+    /// The paths of the sound effects from the ``[sfx]`` section.
+    #[serde(default)]
+    pub sfx: HashMap<String, String>,
+    /// The paths of the voice lines from the ``[voice]`` section.
+    #[serde(default)]
+    pub voice: HashMap<String, String>,
+}
+
+/// The music, sound effects and voice lines read from a Music config file.
+#[derive(Debug, Clone, Default)]
+pub struct MusicLibrary {
+    /// The paths of the music. The music is read from these when it is played.
+    pub music: HashMap<String, PathBuf>,
+    /// The data of the sound effects in bytes.
+    pub sfx: HashMap<String, Arc<[u8]>>,
+    /// The data of the voice lines in bytes.
+    pub voice: HashMap<String, Arc<[u8]>>,
+}
+
 /// Load music from a TOML file
-pub fn load_music_from_file<P: AsRef<Path>>(path: P)
-    -> Result<HashMap<String, Arc<[u8]>>, ConfigImportError> {
+pub fn load_music_from_file<P: AsRef<Path>>(path: P) -> Result<MusicLibrary, ConfigImportError> {
     let mut buf = String::new();
     File::open(path)?.read_to_string(&mut buf)?;
     load_music_from_str(&buf)
 }
 
 /// Load music from a TOML str
-pub fn load_music_from_str(text: &str)
-    -> Result<HashMap<String, Arc<[u8]>>, ConfigImportError> {
-    let map: HashMap<String, String> = from_str(text)?;
+pub fn load_music_from_str(text: &str) -> Result<MusicLibrary, ConfigImportError> {
+    let paths = parse_music_from_str(text)?;
+    Ok(MusicLibrary {
+        music: paths.music.into_iter()
+            .map(|(k, v)| (k, PathBuf::from(v)))
+            .filter(|&(_, ref v)| v.is_file())
+            .collect(),
+        sfx: read_files(&paths.sfx),
+        voice: read_files(&paths.voice),
+    })
+}
+
+fn read_files(map: &HashMap<String, String>) -> HashMap<String, Arc<[u8]>> {
     let mut new_map = HashMap::new();
     for (k, v) in map.iter() {
         if let Ok(mut e) = File::open(v) {
@@ -29,5 +66,28 @@ pub fn load_music_from_str(text: &str)
             new_map.insert(k.to_string(), Arc::from(buf));
         }
     }
-    Ok(new_map)
-}
\ No newline at end of file
+    new_map
+}
+
+/// Read the paths of the music from a TOML str without reading any of the music files.
+pub fn parse_music_from_str(text: &str) -> Result<MusicFromFile, ConfigImportError> {
+    Ok(from_str(text)?)
+}
+
+#[test]
+fn test_parse_music() {
+    let paths = parse_music_from_str(r#"
+    theme = "assets/theme.ogg"
+
+    [sfx]
+    door_slam = "assets/door_slam.ogg"
+
+    [voice]
+    aiko_001 = "assets/voice/aiko_001.ogg"
+    "#).unwrap();
+    assert_eq!(paths.music.len(), 1);
+    assert_eq!(paths.music["theme"], "assets/theme.ogg");
+    assert_eq!(paths.sfx["door_slam"], "assets/door_slam.ogg");
+    assert_eq!(paths.voice["aiko_001"], "assets/voice/aiko_001.ogg");
+    assert!(parse_music_from_str(r#"theme = "theme.ogg""#).unwrap().sfx.is_empty());
+}
@@ -9,8 +9,9 @@ use indexmap::IndexMap;
 use super::super::super::{
     error::ScriptConfigImportError,
     script::{
-        parser::translate,
+        parser::translate_with_source,
         ScriptStep,
+        Source,
     },
 };
 
@@ -25,6 +26,7 @@ pub fn load_scripts_from_file<P: AsRef<Path>>(path: P) -> Result<ScriptsFromFile
 pub fn load_scripts_from_str(text: &str) -> Result<ScriptsFromFile, ScriptConfigImportError> {
     let config: HashMap<String, String> = from_str(text)?;
     let mut map: IndexMap<String, IndexMap<String, Vec<ScriptStep>>> = IndexMap::new();
+    let mut sources = HashMap::new();
     let mut default = None;
     for (k, v) in config.iter() {
         match k.to_lowercase().as_str() {
@@ -34,14 +36,17 @@ pub fn load_scripts_from_str(text: &str) -> Result<ScriptsFromFile, ScriptConfig
             key => {
                 let mut buffer = String::new();
                 File::open(v)?.read_to_string(&mut buffer)?;
-                let script = translate(&buffer)?;
+                let (script, mut source) = translate_with_source(&buffer).map_err(|e| e.with_file(v))?;
+                source.file = Some(v.to_string());
                 map.insert(key.to_string(), script);
+                sources.insert(key.to_string(), source);
             },
         }
     }
     Ok(ScriptsFromFile{
         default,
         map,
+        sources,
     })
 }
 
@@ -51,4 +56,6 @@ pub struct ScriptsFromFile {
     pub default: Option<String>,
     /// A map of all the scripts
     pub map: IndexMap<String, IndexMap<String, Vec<ScriptStep>>>,
+    /// Where each script came from.
+    pub sources: HashMap<String, Source>,
 }
\ No newline at end of file